@@ -0,0 +1,86 @@
+use crate::data::area_info::{self, AreaInfo};
+use crate::data::boss_info;
+use crate::map_builder::MapBuilderArgs;
+use serde::{Deserialize, Serialize};
+
+// floors in each area, the last of which holds the guardian
+pub const AREA_FLOORS: u32 = 3;
+// builder for the room a boss is fought in
+const BOSS_ARENA_BUILDER: usize = 98;
+
+// The area the player is descending through. Each floor is one level harder than the last, and
+// clearing the guardian on the final floor clears the tier the run started from. Once the tier is
+// high enough for one, a boss in its own arena takes the guardian's place
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AreaRun {
+    pub area: Option<AreaInfo>,
+    pub tier: u32,
+    pub floor: u32,
+    #[serde(default)]
+    pub boss: Option<String>,
+}
+
+impl AreaRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&mut self, tier: u32, rng: &mut rltk::RandomNumberGenerator) {
+        self.area = Some(area_info::get_random_area(rng));
+        self.tier = tier;
+        self.floor = 1;
+
+        let bosses: Vec<&str> = boss_info::get_bosses()
+            .iter()
+            .filter(|boss| boss.monster.tier <= tier as i32)
+            .map(|boss| boss.monster.name.as_str())
+            .collect();
+        self.boss = if bosses.is_empty() {
+            None
+        } else {
+            Some(bosses[rng.range(0, bosses.len())].to_string())
+        };
+    }
+
+    pub fn is_final_floor(&self) -> bool {
+        self.area.is_some() && self.floor >= AREA_FLOORS
+    }
+
+    // the boss only shows up on the final floor
+    pub fn get_boss(&self) -> Option<&str> {
+        if self.is_final_floor() {
+            self.boss.as_deref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_level(&self) -> u32 {
+        self.tier + self.floor - 1
+    }
+
+    pub fn map_args(&self) -> Option<MapBuilderArgs> {
+        let area = self.area.as_ref()?;
+        if let Some(boss) = self.get_boss().and_then(boss_info::get_boss_info) {
+            return Some(MapBuilderArgs {
+                width: boss.arena_width,
+                height: boss.arena_height,
+                level: self.get_level(),
+                builder_type: BOSS_ARENA_BUILDER,
+                name: format!("{} {}F", area.name, self.floor),
+                map_color: area.color.clone(),
+                terrain: None,
+            });
+        }
+
+        Some(MapBuilderArgs {
+            width: 80,
+            height: 50,
+            level: self.get_level(),
+            builder_type: area.map_type,
+            name: format!("{} {}F", area.name, self.floor),
+            map_color: area.color.clone(),
+            terrain: area.terrain,
+        })
+    }
+}
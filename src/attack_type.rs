@@ -1,61 +1,142 @@
+use crate::data::attack_info;
 use crate::{AttackIntent, FrameData, RangeType};
-use derivative::Derivative;
 use rltk::Point;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// attacks that the game refers to directly, instead of through data files
+pub const BUILTIN_ATTACKS: [&str; 16] = [
+    "melee",
+    "melee2",
+    "melee_knockback",
+    "shove",
+    "advancing_flip_2",
+    "hook_5",
+    "dodge_3",
+    "projectile_area_4_1",
+    "ranged_stun_6",
+    "greatsword_sweep",
+    "greatsword_cleave",
+    "jab",
+    "flurry",
+    "uppercut",
+    "bow_shot",
+    "bow_stun_shot",
+];
+
+// An attack defined in data/attacks.yaml. Attacks are written out by their id, so saves and
+// data files don't depend on the order of the definitions
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AttackType(usize);
+
+impl AttackType {
+    pub fn from_id(id: &str) -> Option<Self> {
+        attack_info::get_attack_ids()
+            .iter()
+            .position(|other| other == id)
+            .map(AttackType)
+    }
+
+    // only for attacks in BUILTIN_ATTACKS, which are checked when the data is loaded
+    pub fn builtin(id: &str) -> Self {
+        debug_assert!(BUILTIN_ATTACKS.contains(&id));
+        Self::from_id(id).expect("builtin attacks should be checked at startup")
+    }
+
+    pub fn id(&self) -> &'static str {
+        &attack_info::get_attack_ids()[self.0]
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Derivative)]
-#[derivative(Hash)]
-pub enum AttackType {
-    Melee,
-    Melee2,
-    Shove,
-    MeleeKnockback,
-    MeleeStun,
-    MeleeArea { radius: i32 },
-    // Projectile attacks
-    Projectile { radius: i32 },
-    ProjectileStun { radius: i32 },
-    ProjectileKnockback { radius: i32 },
-    ProjectileArea { radius: i32, explosion_size: i32 },
-    OnProjectileAreaHit { radius: i32 },
-    // Ranged attacks
-    Ranged { radius: i32 },
-    RangedStun { radius: i32 },
-    RangedArea { radius: i32, explosion_size: i32 },
-    // Advancing attacks
-    AdvancingKnockback { range: i32 },
-    AdvancingFlip { range: i32 },
-    // Special cases
-    Barrier,
-    Hook { radius: i32 },
-    Dodge { radius: i32 },
-    Recover,
-}
-
-#[derive(PartialEq, Copy, Clone, Debug)]
+impl std::fmt::Debug for AttackType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+impl Serialize for AttackType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for AttackType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        AttackType::from_id(&id)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown attack `{}`", id)))
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Deserialize)]
 pub enum AttackTrait {
-    Damage { amount: i32 },
-    Knockback { amount: i32 },
-    Pull { amount: i32, pass_over: bool },
-    Movement { delay: u32 },
-    Heal { amount: i32 },
-    Invulnerable { duration: u32 },
-    LanceCharge { dir: crate::Direction },
-    NeedsStamina { amount: i32 },
-    FollowsPath { step_delay: u32, on_hit: AttackType },
-    Stun { duration: u32 },
+    Damage {
+        amount: i32,
+    },
+    Knockback {
+        amount: i32,
+    },
+    Pull {
+        amount: i32,
+        pass_over: bool,
+    },
+    Movement {
+        delay: u32,
+    },
+    Heal {
+        amount: i32,
+    },
+    Invulnerable {
+        duration: u32,
+    },
+    LanceCharge {
+        dir: crate::Direction,
+    },
+    NeedsStamina {
+        amount: i32,
+    },
+    FollowsPath {
+        step_delay: u32,
+        on_hit: AttackType,
+    },
+    Stun {
+        duration: u32,
+    },
+    // a status effect on everyone hit, or on the attacker itself
+    Status {
+        kind: crate::status::StatusKind,
+        duration: u32,
+    },
+    SelfStatus {
+        kind: crate::status::StatusKind,
+        duration: u32,
+    },
     CreatesWalls,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AttackData {
     pub needs_target: bool,
     pub needs_path: bool,
     pub name: String,
     pub stam_cost: i32,
     pub attack_type: AttackType,
+    pub bonus: AttackBonus,
     // pub frame_data: FrameData,
 }
 
+// Upgrades applied on top of an attack's data. The bonus rides along with the attack intent,
+// so the attack system can tell an upgraded attack from a regular one
+#[derive(Copy, Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AttackBonus {
+    pub stamina: i32,
+    pub startup: u32,
+    pub radius: i32,
+}
+
 // check if an attack is can be executed
 // this returns the tile that will hit the target
 pub fn is_attack_valid(attack_type: AttackType, from_point: Point, target: Point) -> Option<Point> {
@@ -79,6 +160,12 @@ pub fn each_attack_target(attack_type: AttackType, from_point: Point) -> Vec<Poi
     crate::range_type::resolve_range_at(&shape, from_point)
 }
 
+// same as above, but with the intent's bonus radius
+pub fn each_intent_target(intent: &AttackIntent) -> Vec<Point> {
+    let shape = get_attack_shape(intent.main).grown(intent.bonus.radius);
+    crate::range_type::resolve_range_at(&shape, intent.loc)
+}
+
 // convert an attack into an intent that can be executed by the event system
 pub fn get_attack_intent(
     attack_type: AttackType,
@@ -88,6 +175,7 @@ pub fn get_attack_intent(
     AttackIntent {
         main: attack_type,
         loc,
+        bonus: AttackBonus::default(),
     }
 }
 
@@ -102,147 +190,32 @@ pub fn get_frame_data(attack_type: AttackType) -> FrameData {
     }
 }
 
+pub fn get_bonus_frame_data(attack_type: AttackType, bonus: AttackBonus) -> FrameData {
+    let mut frame = get_frame_data(attack_type);
+    frame.startup = frame.startup.saturating_sub(bonus.startup);
+    frame
+}
+
 pub fn get_attack_range(attack_type: AttackType) -> RangeType {
-    match attack_type {
-        AttackType::Melee => RangeType::Square { size: 1 },
-        AttackType::Melee2 => RangeType::Square { size: 1 },
-        AttackType::Shove => RangeType::Diamond { size: 1 },
-        AttackType::MeleeKnockback => RangeType::Square { size: 1 },
-        AttackType::MeleeStun => RangeType::Square { size: 1 },
-        AttackType::MeleeArea { .. } => RangeType::Single,
-        AttackType::Projectile { radius } => RangeType::Square { size: radius },
-        AttackType::ProjectileStun { radius } => RangeType::Square { size: radius },
-        AttackType::ProjectileKnockback { radius } => RangeType::Square { size: radius },
-        AttackType::ProjectileArea { radius, .. } => RangeType::Square { size: radius },
-        AttackType::OnProjectileAreaHit { radius } => RangeType::Single,
-        AttackType::Ranged { radius } => RangeType::Square { size: radius },
-        AttackType::RangedStun { radius } => RangeType::Square { size: radius },
-        AttackType::RangedArea { radius, .. } => RangeType::Square { size: radius },
-        AttackType::AdvancingKnockback { range } => RangeType::Cross { size: range },
-        AttackType::AdvancingFlip { range } => RangeType::Cross { size: range },
-        AttackType::Barrier => RangeType::Single,
-        AttackType::Hook { radius } => RangeType::Square { size: radius },
-        AttackType::Dodge { radius } => RangeType::Diamond { size: radius },
-        AttackType::Recover => RangeType::Single,
-    }
+    attack_info::get_attack_info(attack_type).range.clone()
 }
 
 pub fn get_attack_shape(attack_type: AttackType) -> RangeType {
-    match attack_type {
-        AttackType::MeleeArea { radius } => RangeType::Square { size: radius },
-        AttackType::OnProjectileAreaHit { radius } => RangeType::SquareInclusive { size: radius },
-        AttackType::RangedArea { explosion_size, .. } => RangeType::SquareInclusive {
-            size: explosion_size,
-        },
-        AttackType::Barrier => RangeType::Ring { size: 3 },
-        _ => RangeType::Single,
-    }
+    attack_info::get_attack_info(attack_type).shape.clone()
 }
 
 pub fn get_startup(attack_type: AttackType) -> u32 {
-    match attack_type {
-        AttackType::MeleeArea { radius } => 10 + 4 * radius as u32,
-        AttackType::MeleeStun => 4,
-        AttackType::Shove => 3,
-        AttackType::OnProjectileAreaHit { .. } => 0,
-        AttackType::ProjectileStun { .. } => 16,
-        AttackType::ProjectileArea { .. } => 22,
-        AttackType::Ranged { .. } => 12,
-        AttackType::RangedStun { .. } => 12,
-        AttackType::RangedArea { .. } => 16,
-        AttackType::AdvancingFlip { .. } => 6,
-        AttackType::AdvancingKnockback { .. } => 6,
-        AttackType::Dodge { .. } => 3,
-        _ => 10,
-    }
+    attack_info::get_attack_info(attack_type).startup
 }
 
 pub fn get_active(attack_type: AttackType) -> u32 {
-    match attack_type {
-        _ => 1,
-    }
+    attack_info::get_attack_info(attack_type).active
 }
 
 pub fn get_recovery(attack_type: AttackType) -> u32 {
-    match attack_type {
-        AttackType::MeleeStun => 24,
-        AttackType::Ranged { .. } => 12,
-        AttackType::RangedStun { .. } => 16,
-        AttackType::RangedArea { .. } => 20,
-        AttackType::Dodge { .. } => 5,
-        _ => 10,
-    }
+    attack_info::get_attack_info(attack_type).recovery
 }
 
-use AttackTrait::*;
 pub fn get_attack_traits(attack_type: AttackType) -> Vec<AttackTrait> {
-    match attack_type {
-        AttackType::Melee => vec![Damage { amount: 1 }],
-        AttackType::Melee2 => vec![Damage { amount: 2 }],
-        AttackType::MeleeKnockback => vec![Damage { amount: 1 }, Knockback { amount: 1 }],
-        AttackType::Shove => vec![Knockback { amount: 3 }, NeedsStamina { amount: 3 }],
-        AttackType::MeleeStun => vec![Stun { duration: 10 }],
-        AttackType::MeleeArea { .. } => vec![Damage { amount: 2 }],
-        AttackType::Projectile { .. } => vec![FollowsPath {
-            step_delay: 3,
-            on_hit: AttackType::Melee,
-        }],
-        AttackType::ProjectileKnockback { .. } => vec![FollowsPath {
-            step_delay: 3,
-            on_hit: AttackType::MeleeKnockback,
-        }],
-        AttackType::ProjectileStun { .. } => vec![FollowsPath {
-            step_delay: 3,
-            on_hit: AttackType::MeleeStun,
-        }],
-        AttackType::ProjectileArea { explosion_size, .. } => vec![
-            FollowsPath {
-                step_delay: 3,
-                on_hit: AttackType::OnProjectileAreaHit {
-                    radius: explosion_size,
-                },
-            },
-            NeedsStamina {
-                amount: crate::player::BOLT_STAM_REQ,
-            },
-        ],
-        AttackType::OnProjectileAreaHit { .. } => vec![Damage { amount: 2 }],
-        AttackType::Ranged { .. } => vec![Damage { amount: 1 }],
-        AttackType::RangedStun { .. } => vec![Damage { amount: 1 }, Stun { duration: 10 }],
-        AttackType::RangedArea { .. } => vec![Damage { amount: 1 }],
-        AttackType::AdvancingKnockback { .. } => vec![
-            Damage { amount: 1 },
-            Knockback { amount: 2 },
-            Movement { delay: 1 },
-        ],
-        AttackType::AdvancingFlip { .. } => vec![
-            Damage { amount: 1 },
-            Pull {
-                amount: 2,
-                pass_over: true,
-            },
-            Movement { delay: 1 },
-            NeedsStamina {
-                amount: crate::player::SUPLEX_STAM_REQ,
-            },
-        ],
-        AttackType::Barrier => vec![CreatesWalls],
-        AttackType::Hook { radius } => vec![
-            Pull {
-                amount: radius - 1,
-                pass_over: false,
-            },
-            NeedsStamina {
-                amount: crate::player::HOOK_STAM_REQ,
-            },
-        ],
-        AttackType::Dodge { .. } => vec![
-            Movement { delay: 0 },
-            Invulnerable { duration: 6 },
-            NeedsStamina {
-                amount: crate::player::DODGE_STAM_REQ,
-            },
-        ], // 24 / 4 = 6 ticks
-        AttackType::Recover => vec![Heal { amount: 2 }],
-    }
+    attack_info::get_attack_info(attack_type).traits.clone()
 }
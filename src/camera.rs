@@ -1,4 +1,5 @@
 use rltk::Point;
+use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
 
 pub const VIEW_W: i32 = 79;
@@ -6,7 +7,7 @@ pub const VIEW_H: i32 = 50;
 pub const MAP_W: i32 = 120;
 pub const MAP_H: i32 = 120;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Camera {
     pub origin: Point,
     pub map_width: i32,
@@ -88,6 +88,14 @@ pub fn attack_intent_color() -> RGB {
     RGB::named(rltk::PURPLE)
 }
 
+pub fn danger_stack_color() -> RGB {
+    RGB::named(rltk::CRIMSON)
+}
+
+pub fn danger_countdown_color() -> RGB {
+    RGB::named(rltk::WHITE)
+}
+
 pub fn tiles_in_range_color() -> RGB {
     RGB::named(rltk::BLUE)
 }
@@ -128,6 +136,26 @@ pub fn map_shallow_water_color() -> RGB {
     RGB::named(rltk::LIGHTBLUE)
 }
 
+pub fn map_lava_color() -> RGB {
+    RGB::named(rltk::ORANGERED)
+}
+
+pub fn map_spikes_color() -> RGB {
+    RGB::named(rltk::SILVER)
+}
+
+pub fn map_ice_color() -> RGB {
+    RGB::named(rltk::LIGHTCYAN)
+}
+
+pub fn map_grass_color() -> RGB {
+    RGB::named(rltk::FORESTGREEN)
+}
+
+pub fn map_fire_color() -> RGB {
+    RGB::named(rltk::ORANGE)
+}
+
 pub fn frame_startup_color() -> RGB {
     RGB::named(rltk::GREEN3)
 }
@@ -159,3 +187,23 @@ pub fn enemy_melee_color() -> RGB {
 pub fn enemy_mage_color() -> RGB {
     RGB::named(rltk::LIGHTYELLOW)
 }
+
+pub fn burn_color() -> RGB {
+    RGB::named(rltk::ORANGE)
+}
+
+pub fn freeze_color() -> RGB {
+    RGB::named(rltk::CYAN)
+}
+
+pub fn bleed_color() -> RGB {
+    RGB::named(rltk::CRIMSON)
+}
+
+pub fn slow_color() -> RGB {
+    RGB::named(rltk::STEELBLUE)
+}
+
+pub fn haste_color() -> RGB {
+    RGB::named(rltk::GOLD)
+}
@@ -1,8 +1,9 @@
 use rltk::{Point, RGB};
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs::Component;
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -14,7 +15,7 @@ impl Position {
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Renderable {
     pub symbol: rltk::FontCharType,
     pub fg: RGB,
@@ -22,59 +23,71 @@ pub struct Renderable {
     pub zindex: u32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Player;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Viewshed {
     pub visible: Vec<Point>,
     pub dirty: bool,
     pub range: i32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct CanActFlag {
     pub is_reaction: bool,
     pub reaction_target: Option<Entity>,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct CanReactFlag;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Schedulable {
     pub current: i32,
     pub base: i32,
     pub delta: i32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct ParticleLifetime {
     pub base: f32,
     pub remaining: f32,
     pub should_fade: bool,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct BlocksTile;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct BlocksVision;
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Health {
     pub current: i32,
     pub max: i32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Stamina {
     pub current: i32,
     pub max: i32,
     pub recover: bool,
 }
 
-#[derive(Component, Copy, Clone)]
+// money awarded when knocked out
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Bounty {
+    pub money: u32,
+}
+
+// flat reduction to damage taken, a hit always does at least 1 damage
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Armor {
+    pub amount: i32,
+}
+
+#[derive(Component, Copy, Clone, Serialize, Deserialize)]
 pub struct FrameData {
     pub startup: u32,
     pub active: u32,
@@ -84,131 +97,157 @@ pub struct FrameData {
     pub linger_time: i32,
 }
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Serialize, Deserialize)]
 pub struct AttackIntent {
     pub main: crate::AttackType,
     pub loc: Point,
+    pub bonus: crate::AttackBonus,
 }
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Serialize, Deserialize)]
 pub struct MoveIntent {
     pub loc: rltk::Point,
     pub force_facing: Option<crate::Direction>,
     pub delay: u32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct AttackPath {
     pub path: Vec<rltk::Point>,
     pub index: usize,
     pub step_delay: u32,
     pub cur_delay: u32,
     pub on_hit: crate::AttackType,
+    pub bonus: crate::AttackBonus,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Moveset {
     pub moves: Vec<(crate::AttackType, f32)>,
     pub bump_attack: crate::AttackType,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Viewable {
     pub name: String,
     pub description: Vec<String>,
     pub seen: bool,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct ViewableIndex {
     pub list_index: Option<u32>,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct AttackInProgress;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct BlockAttack {
     pub block_amount: u32,
+    // ticks left in which a hit is parried instead of blocked
+    pub parry_window: u32,
 }
 
-#[derive(Component)]
+// raises a guard instead of stepping aside when reacting
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct CanBlock {
+    pub block_amount: u32,
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct AiState {
     pub status: crate::Behavior,
-    pub prev_path: Option<rltk::NavigationPath>,
-    pub path_step: usize,
+    // monsters only lose their nerve once
+    #[serde(default)]
+    pub has_fled: bool,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct TrapAiState {
     pub status: crate::Behavior,
 }
 
-#[derive(Component)]
-pub struct Heal {
-    pub amount: u32,
-}
-
-#[derive(Component)]
-pub struct EarthScroll {
-    pub radius: i32,
-    pub active_prob: f32,
+// something on the ground that can be picked up, named as in data/items.yaml
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
 }
 
-#[derive(Component)]
-pub struct Item;
-
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Openable;
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Fragile {
     pub lifetime: u32,
     pub was_hit: bool,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct MultiTile {
     pub part_list: Vec<crate::MonsterPart>,
     pub bounds: rltk::Rect,
 }
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Serialize, Deserialize)]
 pub struct Facing {
     pub direction: crate::Direction,
 }
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct PartMoveIntent {
     pub part_delta: Vec<rltk::Point>,
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct PushForce {
     pub delta: rltk::Point,
+    // dealt for each wall or creature the push slams into
+    #[serde(default)]
+    pub impact_damage: i32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum NpcType {
     Blacksmith,
     Shopkeeper,
     Handler,
 }
 
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Npc {
     pub npc_type: NpcType,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Invulnerable {
     pub duration: u32,
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Stunned {
     pub duration: u32,
 }
 
-#[derive(Component)]
+// timed effects like burning or haste, ticked down by the turn system
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct StatusEffects {
+    pub effects: Vec<crate::status::StatusEffect>,
+}
+
+#[derive(Component, Clone)]
 pub struct MissionTarget;
+
+// holds the final floor of an area, the exit only opens once it is down
+#[derive(Component, Clone)]
+pub struct Guardian;
+
+// monsters spawned together in one area of the map, who fight as a team
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Squad {
+    pub id: i32,
+}
+
+// a multi-tile monster that grows more desperate with every part broken off it
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Boss;
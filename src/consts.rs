@@ -1,2 +1,38 @@
 pub const WALL_HIT_STUN_DURATION: u32 = 25;
+// damage for slamming a large body into something it was shoved against
+pub const PUSH_IMPACT_DAMAGE: i32 = 1;
 pub const FRAME_LINGER_TIME: i32 = 10;
+
+// money for knocking out a monster, for each tier of difficulty
+pub const MONEY_PER_TIER: u32 = 2;
+// a guard raised this many ticks before a hit lands parries it
+pub const PARRY_WINDOW: u32 = 6;
+pub const PLAYER_BLOCK_AMOUNT: u32 = 1;
+// chance a monster that blocks times its guard well enough to parry
+pub const MONSTER_PARRY_CHANCE: f32 = 0.25;
+pub const BLOCK_STAMINA_COST: i32 = 1;
+// guardians are the strongest monster a floor allows, with this much more health and bounty
+pub const GUARDIAN_MULTIPLIER: i32 = 3;
+// every broken part takes a tick off a boss's turns, down to this
+pub const BOSS_MIN_SPEED: i32 = 3;
+// money for clearing an arena, for each level of the arena
+pub const CLEAR_BONUS_PER_LEVEL: u32 = 5;
+// damage for being knocked onto hazardous terrain
+pub const LAVA_DAMAGE: i32 = 2;
+pub const SPIKE_DAMAGE: i32 = 1;
+// burning grass spreads to its neighbours once a turn until it burns out
+pub const FIRE_DURATION: u32 = 24;
+pub const FIRE_SPREAD_DELAY: u32 = 6;
+// how long standing in fire or lava keeps someone burning
+pub const TERRAIN_BURN_DURATION: u32 = 30;
+// some monsters are found asleep, and only wake to noise or the player coming close
+pub const SLEEP_CHANCE: f32 = 0.3;
+pub const WAKE_DISTANCE: f32 = 2.0;
+// how far the sounds of a fight carry
+pub const COMBAT_NOISE_RADIUS: i32 = 5;
+pub const IMPACT_NOISE_RADIUS: i32 = 7;
+// monsters that spot the player call over anyone this close
+pub const ALERT_RADIUS: i32 = 8;
+// badly hurt monsters run once, for at most this many turns, or until they reach a friend
+pub const FLEE_TURNS: u32 = 12;
+pub const REGROUP_DISTANCE: f32 = 2.0;
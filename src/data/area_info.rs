@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 lazy_static! {
     static ref AREA_DATA: AreaData = load_area_data();
@@ -10,7 +10,7 @@ struct AreaData {
     areas: Vec<AreaInfo>,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct AreaInfo {
     pub name: String,
 
@@ -19,6 +19,10 @@ pub struct AreaInfo {
 
     #[serde(default = "default_color")]
     pub color: String,
+
+    // terrain scattered through the area's maps
+    #[serde(default)]
+    pub terrain: Option<crate::TileType>,
 }
 
 fn default_color() -> String {
@@ -52,6 +56,7 @@ pub fn get_random_area(rng: &mut rltk::RandomNumberGenerator) -> AreaInfo {
         name: get_combined_name(&prefix_info, &area_info),
         map_type: get_combined_generator(&prefix_info, &area_info),
         color: get_combined_color(&prefix_info, &area_info),
+        terrain: prefix_info.terrain.or(area_info.terrain),
     }
 }
 
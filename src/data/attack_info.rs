@@ -0,0 +1,165 @@
+use super::DataEntry;
+use crate::{AttackTrait, AttackType, RangeType};
+use serde::Deserialize;
+use serde_yaml::Mapping;
+
+const DEFAULT_STARTUP: u32 = 10;
+const DEFAULT_ACTIVE: u32 = 1;
+const DEFAULT_RECOVERY: u32 = 10;
+
+const FIELDS: [&str; 7] = [
+    "id", "range", "shape", "startup", "active", "recovery", "traits",
+];
+
+// Ids are read on their own first, so attacks can refer to each other (and to attacks later in
+// the file) while the rest of the data is still being read
+lazy_static! {
+    static ref ATTACK_IDS: Result<Vec<String>, String> = load_attack_ids();
+    static ref ATTACK_DATA: Result<Vec<AttackInfo>, String> = load_attack_data();
+}
+
+pub struct AttackInfo {
+    pub id: String,
+    pub range: RangeType,
+    pub shape: RangeType,
+    pub startup: u32,
+    pub active: u32,
+    pub recovery: u32,
+    pub traits: Vec<AttackTrait>,
+}
+
+#[derive(Deserialize)]
+struct AttackFile {
+    attacks: Vec<Mapping>,
+}
+
+rltk::embedded_resource!(ATTACK_RAW_DATA, "../../data/attacks.yaml");
+
+fn read_attack_file() -> Result<AttackFile, String> {
+    rltk::link_resource!(ATTACK_RAW_DATA, "../../data/attacks.yaml");
+
+    let raw_string = super::get_raw_data("../../data/attacks.yaml");
+    serde_yaml::from_str(&raw_string).map_err(|e| format!("attacks.yaml: {}", e))
+}
+
+fn load_attack_ids() -> Result<Vec<String>, String> {
+    let file = read_attack_file()?;
+    let mut ids: Vec<String> = Vec::new();
+
+    for (index, mapping) in file.attacks.iter().enumerate() {
+        let entry = DataEntry::new("attack", "id", &FIELDS, mapping, index)
+            .map_err(|e| format!("attacks.yaml: {}", e))?;
+        if ids.contains(&entry.name) {
+            return Err(format!(
+                "attacks.yaml: attack {} is defined more than once",
+                entry.name
+            ));
+        }
+
+        ids.push(entry.name);
+    }
+
+    Ok(ids)
+}
+
+fn load_attack_data() -> Result<Vec<AttackInfo>, String> {
+    ATTACK_IDS.as_ref().map_err(Clone::clone)?;
+
+    let file = read_attack_file()?;
+    let mut attacks = Vec::new();
+
+    for (index, mapping) in file.attacks.iter().enumerate() {
+        let entry = DataEntry::new("attack", "id", &FIELDS, mapping, index)
+            .map_err(|e| format!("attacks.yaml: {}", e))?;
+        attacks.push(parse_attack(entry).map_err(|e| format!("attacks.yaml: {}", e))?);
+    }
+
+    check_on_hit_cycles(&attacks).map_err(|e| format!("attacks.yaml: {}", e))?;
+    Ok(attacks)
+}
+
+fn parse_attack(entry: DataEntry) -> Result<AttackInfo, String> {
+    let traits: Vec<AttackTrait> = entry.optional("traits")?.unwrap_or_default();
+    let active = entry.optional("active")?.unwrap_or(DEFAULT_ACTIVE);
+    if active == 0 {
+        return Err(entry.error("active", "must be at least 1".to_string()));
+    }
+
+    Ok(AttackInfo {
+        range: entry.optional("range")?.unwrap_or(RangeType::Single),
+        shape: entry.optional("shape")?.unwrap_or(RangeType::Single),
+        startup: entry.optional("startup")?.unwrap_or(DEFAULT_STARTUP),
+        active,
+        recovery: entry.optional("recovery")?.unwrap_or(DEFAULT_RECOVERY),
+        traits,
+        id: entry.name,
+    })
+}
+
+fn on_hit_attacks(info: &AttackInfo) -> impl Iterator<Item = AttackType> + '_ {
+    info.traits.iter().filter_map(|tr| match tr {
+        AttackTrait::FollowsPath { on_hit, .. } => Some(*on_hit),
+        _ => None,
+    })
+}
+
+// a projectile that hits with itself would never stop firing
+fn check_on_hit_cycles(attacks: &[AttackInfo]) -> Result<(), String> {
+    for start in 0..attacks.len() {
+        if let Some(cycle) = find_on_hit_cycle(attacks, start, &mut Vec::new()) {
+            let names: Vec<&str> = cycle.iter().map(|i| attacks[*i].id.as_str()).collect();
+            return Err(format!(
+                "attack {} has an on_hit chain that loops: {}",
+                attacks[start].id,
+                names.join(" -> ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn find_on_hit_cycle(
+    attacks: &[AttackInfo],
+    index: usize,
+    chain: &mut Vec<usize>,
+) -> Option<Vec<usize>> {
+    if chain.contains(&index) {
+        chain.push(index);
+        return Some(chain.clone());
+    }
+
+    chain.push(index);
+    for next in on_hit_attacks(&attacks[index]) {
+        if let Some(cycle) = find_on_hit_cycle(attacks, next.index(), chain) {
+            return Some(cycle);
+        }
+    }
+    chain.pop();
+
+    None
+}
+
+pub fn check_attack_data() -> Result<(), String> {
+    ATTACK_DATA.as_ref().map_err(Clone::clone)?;
+
+    for id in crate::attack_type::BUILTIN_ATTACKS {
+        if AttackType::from_id(id).is_none() {
+            return Err(format!("attacks.yaml: attack {} is missing", id));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn get_attack_ids() -> &'static [String] {
+    ATTACK_IDS
+        .as_ref()
+        .expect("attack data should be checked at startup")
+}
+
+pub fn get_attack_info(attack_type: AttackType) -> &'static AttackInfo {
+    &ATTACK_DATA
+        .as_ref()
+        .expect("attack data should be checked at startup")[attack_type.index()]
+}
@@ -0,0 +1,180 @@
+use super::monster_info::{self, MonsterInfo, MoveInfo};
+use super::DataEntry;
+use crate::AttackType;
+use serde::Deserialize;
+use serde_yaml::Mapping;
+
+const FIELDS: [&str; 14] = [
+    "name",
+    "glyph",
+    "color",
+    "health",
+    "speed",
+    "vision",
+    "moves",
+    "bump_attack",
+    "description",
+    "tier",
+    "reacts",
+    "block",
+    "arena",
+    "parts",
+];
+
+const PART_FIELDS: [&str; 6] = ["name", "glyph", "tiles", "health", "moves", "loot"];
+
+// the arena has to leave enough floor for the level to count as built
+const MIN_ARENA_WIDTH: i32 = 20;
+const MIN_ARENA_HEIGHT: i32 = 14;
+
+lazy_static! {
+    static ref BOSS_DATA: Result<Vec<BossInfo>, String> = load_boss_data();
+}
+
+#[derive(Clone)]
+pub struct BossInfo {
+    pub monster: MonsterInfo,
+    pub arena_width: i32,
+    pub arena_height: i32,
+    pub parts: Vec<BossPartInfo>,
+}
+
+#[derive(Clone)]
+pub struct BossPartInfo {
+    pub name: String,
+    pub glyph: rltk::FontCharType,
+    pub tiles: Vec<rltk::Point>,
+    pub health: i32,
+    pub moves: Vec<(AttackType, f32)>,
+    pub loot: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArenaInfo {
+    width: i32,
+    height: i32,
+}
+
+#[derive(Deserialize)]
+struct BossData {
+    bosses: Vec<Mapping>,
+}
+
+rltk::embedded_resource!(BOSS_RAW_DATA, "../../data/bosses.yaml");
+
+fn load_boss_data() -> Result<Vec<BossInfo>, String> {
+    rltk::link_resource!(BOSS_RAW_DATA, "../../data/bosses.yaml");
+
+    let raw_string = super::get_raw_data("../../data/bosses.yaml");
+    parse_boss_data(&raw_string).map_err(|e| format!("bosses.yaml: {}", e))
+}
+
+pub fn parse_boss_data(text: &str) -> Result<Vec<BossInfo>, String> {
+    let data: BossData = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let mut bosses: Vec<BossInfo> = Vec::new();
+
+    for (index, mapping) in data.bosses.iter().enumerate() {
+        let entry = DataEntry::new("boss", "name", &FIELDS, mapping, index)?;
+
+        let arena: ArenaInfo = entry.required("arena")?;
+        if arena.width < MIN_ARENA_WIDTH || arena.height < MIN_ARENA_HEIGHT {
+            return Err(entry.error(
+                "arena",
+                format!("must be at least {}x{}", MIN_ARENA_WIDTH, MIN_ARENA_HEIGHT),
+            ));
+        }
+
+        let part_maps: Vec<Mapping> = entry.required("parts")?;
+        let mut parts: Vec<BossPartInfo> = Vec::new();
+        for (part_index, part_map) in part_maps.iter().enumerate() {
+            let part_entry =
+                DataEntry::new("boss part", "name", &PART_FIELDS, part_map, part_index)?;
+            let part = parse_part(part_entry)?;
+
+            // parts can't cover the core or each other
+            let overlaps = part.tiles.iter().any(|tile| {
+                *tile == rltk::Point::zero() || parts.iter().any(|other| other.tiles.contains(tile))
+            });
+            if overlaps {
+                return Err(entry.error(
+                    "parts",
+                    format!("{} overlaps another part of the body", part.name),
+                ));
+            }
+
+            parts.push(part);
+        }
+
+        let boss = BossInfo {
+            monster: monster_info::parse_monster(entry)?,
+            arena_width: arena.width,
+            arena_height: arena.height,
+            parts,
+        };
+
+        if bosses
+            .iter()
+            .any(|other| other.monster.name == boss.monster.name)
+        {
+            return Err(format!(
+                "boss {} is defined more than once",
+                boss.monster.name
+            ));
+        }
+
+        bosses.push(boss);
+    }
+
+    Ok(bosses)
+}
+
+fn parse_part(entry: DataEntry) -> Result<BossPartInfo, String> {
+    let glyph = monster_info::parse_glyph(&entry)?;
+
+    let tiles: Vec<(i32, i32)> = entry.required("tiles")?;
+    if tiles.is_empty() {
+        return Err(entry.error("tiles", "needs at least one tile".to_string()));
+    }
+
+    let health: i32 = entry.required("health")?;
+    if health <= 0 {
+        return Err(entry.error("health", "must be positive".to_string()));
+    }
+
+    let moves: Vec<MoveInfo> = entry.optional("moves")?.unwrap_or_default();
+    let moves = monster_info::parse_moves(&entry, moves)?;
+
+    let loot: Option<String> = entry.optional("loot")?;
+    if let Some(loot) = &loot {
+        if super::item_info::get_item_info(loot).is_none() {
+            return Err(entry.error("loot", format!("{} is not in items.yaml", loot)));
+        }
+    }
+
+    Ok(BossPartInfo {
+        glyph,
+        tiles: tiles
+            .iter()
+            .map(|(x, y)| rltk::Point::new(*x, *y))
+            .collect(),
+        health,
+        moves,
+        loot,
+        name: entry.name,
+    })
+}
+
+pub fn check_boss_data() -> Result<(), String> {
+    BOSS_DATA.as_ref().map(|_| ()).map_err(Clone::clone)
+}
+
+pub fn get_bosses() -> &'static [BossInfo] {
+    BOSS_DATA
+        .as_ref()
+        .expect("boss data should be checked at startup")
+}
+
+pub fn get_boss_info(name: &str) -> Option<&'static BossInfo> {
+    get_bosses().iter().find(|boss| boss.monster.name == name)
+}
@@ -0,0 +1,69 @@
+use super::DataEntry;
+use crate::AttackType;
+use serde::Deserialize;
+use serde_yaml::Mapping;
+
+const FIELDS: [&str; 4] = ["name", "description", "price", "attack"];
+
+lazy_static! {
+    static ref ITEM_DATA: Result<Vec<ItemInfo>, String> = load_item_data();
+}
+
+#[derive(Clone)]
+pub struct ItemInfo {
+    pub name: String,
+    pub description: String,
+    pub price: u32,
+    pub attack: AttackType,
+}
+
+#[derive(Deserialize)]
+struct ItemData {
+    items: Vec<Mapping>,
+}
+
+rltk::embedded_resource!(ITEM_RAW_DATA, "../../data/items.yaml");
+
+fn load_item_data() -> Result<Vec<ItemInfo>, String> {
+    rltk::link_resource!(ITEM_RAW_DATA, "../../data/items.yaml");
+
+    let raw_string = super::get_raw_data("../../data/items.yaml");
+    parse_item_data(&raw_string).map_err(|e| format!("items.yaml: {}", e))
+}
+
+pub fn parse_item_data(text: &str) -> Result<Vec<ItemInfo>, String> {
+    let data: ItemData = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let mut items: Vec<ItemInfo> = Vec::new();
+
+    for (index, mapping) in data.items.iter().enumerate() {
+        let entry = DataEntry::new("item", "name", &FIELDS, mapping, index)?;
+        let item = ItemInfo {
+            description: entry.optional("description")?.unwrap_or_default(),
+            price: entry.required("price")?,
+            attack: entry.required("attack")?,
+            name: entry.name,
+        };
+
+        if items.iter().any(|other| other.name == item.name) {
+            return Err(format!("item {} is defined more than once", item.name));
+        }
+
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+pub fn check_item_data() -> Result<(), String> {
+    ITEM_DATA.as_ref().map(|_| ()).map_err(Clone::clone)
+}
+
+pub fn get_items() -> &'static [ItemInfo] {
+    ITEM_DATA
+        .as_ref()
+        .expect("item data should be checked at startup")
+}
+
+pub fn get_item_info(name: &str) -> Option<&'static ItemInfo> {
+    get_items().iter().find(|item| item.name == name)
+}
@@ -1 +1,91 @@
-mod area_info;
+pub mod area_info;
+pub mod attack_info;
+pub mod boss_info;
+pub mod item_info;
+pub mod monster_info;
+
+use serde::de::DeserializeOwned;
+use serde_yaml::Mapping;
+
+// reports a broken data file up front instead of when it is first used
+pub fn check_data() -> Result<(), String> {
+    attack_info::check_attack_data()?;
+    monster_info::check_monster_data()?;
+    item_info::check_item_data()?;
+    // boss loot refers to items, so they are checked last
+    boss_info::check_boss_data()
+}
+
+// One entry in a list of definitions. Fields are read one at a time, so errors can say which
+// entry and which field is wrong
+pub struct DataEntry<'a> {
+    kind: &'static str,
+    pub name: String,
+    mapping: &'a Mapping,
+}
+
+impl<'a> DataEntry<'a> {
+    pub fn new(
+        kind: &'static str,
+        name_field: &str,
+        fields: &[&str],
+        mapping: &'a Mapping,
+        index: usize,
+    ) -> Result<Self, String> {
+        let name = match mapping.get(name_field) {
+            None => {
+                return Err(format!(
+                    "{} {} is missing field `{}`",
+                    kind,
+                    index + 1,
+                    name_field
+                ))
+            }
+            Some(value) => serde_yaml::from_value(value.clone())
+                .map_err(|e| format!("{} {}, field `{}`: {}", kind, index + 1, name_field, e))?,
+        };
+
+        for key in mapping.keys() {
+            let key = key.as_str().unwrap_or_default();
+            if !fields.contains(&key) {
+                return Err(format!("{} {} has unknown field `{}`", kind, name, key));
+            }
+        }
+
+        Ok(Self {
+            kind,
+            name,
+            mapping,
+        })
+    }
+
+    pub fn required<T: DeserializeOwned>(&self, field: &str) -> Result<T, String> {
+        self.optional(field)?
+            .ok_or_else(|| format!("{} {} is missing field `{}`", self.kind, self.name, field))
+    }
+
+    pub fn optional<T: DeserializeOwned>(&self, field: &str) -> Result<Option<T>, String> {
+        self.mapping
+            .get(field)
+            .map(|value| {
+                serde_yaml::from_value(value.clone()).map_err(|e| self.error(field, e.to_string()))
+            })
+            .transpose()
+    }
+
+    pub fn error(&self, field: &str, msg: String) -> String {
+        format!("{} {}, field `{}`: {}", self.kind, self.name, field, msg)
+    }
+}
+
+// embedded data files are looked up by the path they were embedded with
+fn get_raw_data(path: &str) -> String {
+    let raw_data = rltk::embedding::EMBED
+        .lock()
+        .get_resource(path.to_string())
+        .unwrap();
+
+    std::str::from_utf8(raw_data)
+        .expect("Unable to convert to a valid UTF-8 string.")
+        .to_string()
+}
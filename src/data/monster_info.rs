@@ -0,0 +1,171 @@
+use super::DataEntry;
+use crate::AttackType;
+use rltk::RGB;
+use serde::Deserialize;
+use serde_yaml::Mapping;
+
+const DEFAULT_SPEED: i32 = 6;
+const DEFAULT_VISION: i32 = 10;
+
+pub(super) const FIELDS: [&str; 12] = [
+    "name",
+    "glyph",
+    "color",
+    "health",
+    "speed",
+    "vision",
+    "moves",
+    "bump_attack",
+    "description",
+    "tier",
+    "reacts",
+    "block",
+];
+
+lazy_static! {
+    static ref MONSTER_DATA: Result<Vec<MonsterInfo>, String> = load_monster_data();
+}
+
+#[derive(Clone)]
+pub struct MonsterInfo {
+    pub name: String,
+    pub glyph: rltk::FontCharType,
+    pub color: RGB,
+    pub health: i32,
+    pub speed: i32,
+    pub vision: i32,
+    pub moves: Vec<(AttackType, f32)>,
+    pub bump_attack: AttackType,
+    pub description: Vec<String>,
+    pub tier: i32,
+    pub reacts: bool,
+    pub block: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct MoveInfo {
+    attack: AttackType,
+    weight: f32,
+}
+
+#[derive(Deserialize)]
+struct MonsterData {
+    monsters: Vec<Mapping>,
+}
+
+rltk::embedded_resource!(MONSTER_RAW_DATA, "../../data/monsters.yaml");
+
+fn load_monster_data() -> Result<Vec<MonsterInfo>, String> {
+    rltk::link_resource!(MONSTER_RAW_DATA, "../../data/monsters.yaml");
+
+    let raw_string = super::get_raw_data("../../data/monsters.yaml");
+    parse_monster_data(&raw_string).map_err(|e| format!("monsters.yaml: {}", e))
+}
+
+pub fn parse_monster_data(text: &str) -> Result<Vec<MonsterInfo>, String> {
+    let data: MonsterData = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let mut monsters: Vec<MonsterInfo> = Vec::new();
+
+    for (index, mapping) in data.monsters.iter().enumerate() {
+        let entry = DataEntry::new("monster", "name", &FIELDS, mapping, index)?;
+        let monster = parse_monster(entry)?;
+        if monsters.iter().any(|other| other.name == monster.name) {
+            return Err(format!(
+                "monster {} is defined more than once",
+                monster.name
+            ));
+        }
+
+        monsters.push(monster);
+    }
+
+    Ok(monsters)
+}
+
+pub(super) fn parse_monster(entry: DataEntry) -> Result<MonsterInfo, String> {
+    let glyph = parse_glyph(&entry)?;
+
+    let color: String = entry.required("color")?;
+    let color = parse_color(&color).ok_or_else(|| {
+        entry.error(
+            "color",
+            format!("{} is not melee, ranged, mage or a hex color", color),
+        )
+    })?;
+
+    let health: i32 = entry.required("health")?;
+    if health <= 0 {
+        return Err(entry.error("health", "must be positive".to_string()));
+    }
+
+    let speed = entry.optional("speed")?.unwrap_or(DEFAULT_SPEED);
+    if speed <= 0 {
+        return Err(entry.error("speed", "must be positive".to_string()));
+    }
+
+    let moves: Vec<MoveInfo> = entry.required("moves")?;
+    if moves.is_empty() {
+        return Err(entry.error("moves", "needs at least one attack".to_string()));
+    }
+    let moves = parse_moves(&entry, moves)?;
+
+    let tier: i32 = entry.required("tier")?;
+    if tier < 1 {
+        return Err(entry.error("tier", "must be at least 1".to_string()));
+    }
+
+    Ok(MonsterInfo {
+        glyph,
+        color,
+        health,
+        speed,
+        vision: entry.optional("vision")?.unwrap_or(DEFAULT_VISION),
+        moves,
+        bump_attack: entry.required("bump_attack")?,
+        description: entry.optional("description")?.unwrap_or_default(),
+        tier,
+        reacts: entry.optional("reacts")?.unwrap_or(false),
+        block: entry.optional("block")?.unwrap_or(0),
+        name: entry.name,
+    })
+}
+
+pub(super) fn parse_glyph(entry: &DataEntry) -> Result<rltk::FontCharType, String> {
+    let glyph: String = entry.required("glyph")?;
+    let mut chars = glyph.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(rltk::to_cp437(c)),
+        _ => Err(entry.error("glyph", "expected a single character".to_string())),
+    }
+}
+
+pub(super) fn parse_moves(
+    entry: &DataEntry,
+    moves: Vec<MoveInfo>,
+) -> Result<Vec<(AttackType, f32)>, String> {
+    if moves.iter().any(|m| m.weight <= 0.0) {
+        return Err(entry.error("moves", "weights must be positive".to_string()));
+    }
+
+    Ok(moves.iter().map(|m| (m.attack, m.weight)).collect())
+}
+
+fn parse_color(color: &str) -> Option<RGB> {
+    match color {
+        "melee" => Some(crate::enemy_melee_color()),
+        "ranged" => Some(crate::enemy_ranged_color()),
+        "mage" => Some(crate::enemy_mage_color()),
+        _ => RGB::from_hex(color).ok(),
+    }
+}
+
+pub fn check_monster_data() -> Result<(), String> {
+    MONSTER_DATA.as_ref().map(|_| ()).map_err(Clone::clone)
+}
+
+pub fn get_monsters() -> &'static [MonsterInfo] {
+    MONSTER_DATA
+        .as_ref()
+        .expect("monster data should be checked at startup")
+}
@@ -1,7 +1,8 @@
 use derivative::Derivative;
 use rltk::Point;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Derivative)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Derivative, Serialize, Deserialize)]
 #[derivative(Hash)]
 pub enum Direction {
     N,
@@ -0,0 +1,136 @@
+use crate::Map;
+use rltk::{Algorithm2D, Point};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// walking over hazards is only worth it when there is no other way round
+const HAZARD_COST: i32 = 5;
+const UNREACHED: i32 = i32::MAX;
+
+// where a flow map leads
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FlowGoal {
+    // as close to a tile as possible
+    Point(usize),
+    // any tile exactly this far from the center, for shooting from
+    Range { center: usize, range: i32 },
+}
+
+// Walking distance from every tile to a goal, over terrain only. Creatures don't block the field,
+// so a crowd in a corridor doesn't send everyone behind it the long way round
+pub struct FlowMap {
+    distances: Vec<i32>,
+}
+
+impl FlowMap {
+    pub fn new(map: &Map, goal: FlowGoal, footprint: &[Point]) -> Self {
+        let mut distances = vec![UNREACHED; map.tiles.len()];
+        let mut open = BinaryHeap::new();
+
+        let goals: Vec<usize> = match goal {
+            FlowGoal::Point(index) => vec![index],
+            FlowGoal::Range { center, range } => {
+                let center = map.index_to_point2d(center);
+                (0..map.tiles.len())
+                    .filter(|index| {
+                        let point = map.index_to_point2d(*index);
+                        let distance =
+                            std::cmp::max((point.x - center.x).abs(), (point.y - center.y).abs());
+                        distance == range && is_passable(map, point, footprint)
+                    })
+                    .collect()
+            }
+        };
+
+        for index in goals {
+            distances[index] = 0;
+            open.push(Reverse((0, index)));
+        }
+
+        while let Some(Reverse((distance, index))) = open.pop() {
+            if distance > distances[index] {
+                continue;
+            }
+
+            let point = map.index_to_point2d(index);
+            for dir in [
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0),
+            ] {
+                let next = point + dir;
+                if !is_passable(map, next, footprint) {
+                    continue;
+                }
+
+                let next_index = map.point2d_to_index(next);
+                let cost = if map.is_tile_hazard(next.x, next.y) {
+                    HAZARD_COST
+                } else {
+                    1
+                };
+
+                if distance + cost < distances[next_index] {
+                    distances[next_index] = distance + cost;
+                    open.push(Reverse((distance + cost, next_index)));
+                }
+            }
+        }
+
+        Self { distances }
+    }
+
+    pub fn get_distance(&self, index: usize) -> Option<i32> {
+        Some(self.distances[index]).filter(|distance| *distance != UNREACHED)
+    }
+}
+
+// a body fits on a tile if none of the tiles it covers are walls, water or lava
+fn is_passable(map: &Map, point: Point, footprint: &[Point]) -> bool {
+    footprint.iter().all(|offset| {
+        let tile = point + *offset;
+        map.in_bounds(tile) && !map.is_terrain_blocked(map.point2d_to_index(tile))
+    })
+}
+
+// Flow maps for one pass of the AI, built the first time a monster asks for one and then shared
+// by everyone heading the same way with the same shape of body
+#[derive(Default)]
+pub struct FlowMaps {
+    fields: HashMap<(FlowGoal, Vec<(i32, i32)>), FlowMap>,
+}
+
+impl FlowMaps {
+    pub fn get(&mut self, map: &Map, goal: FlowGoal, footprint: &[Point]) -> &FlowMap {
+        let key = (
+            goal,
+            footprint.iter().map(|point| (point.x, point.y)).collect(),
+        );
+        self.fields
+            .entry(key)
+            .or_insert_with(|| FlowMap::new(map, goal, footprint))
+    }
+
+    // the open tile next to us that is closest to the goal, as long as it gets us closer
+    pub fn next_step(
+        &mut self,
+        map: &mut Map,
+        ent: specs::Entity,
+        from: Point,
+        goal: FlowGoal,
+        multi: Option<&crate::MultiTile>,
+    ) -> Option<Point> {
+        let from_index = map.point2d_to_index(from);
+        let exits = map.get_available_exits_for(from_index, ent, multi);
+        let field = self.get(map, goal, &crate::get_footprint(multi));
+        let current = field.get_distance(from_index).unwrap_or(UNREACHED);
+
+        exits
+            .iter()
+            .filter_map(|(exit, _)| field.get_distance(*exit).map(|distance| (*exit, distance)))
+            .filter(|(_, distance)| *distance < current)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(exit, _)| map.index_to_point2d(exit))
+    }
+}
@@ -1,8 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     pub text: String,
     pub count: u32,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameLog {
     pub entries: Vec<LogEntry>,
     pub pending: Option<String>,
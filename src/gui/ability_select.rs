@@ -5,7 +5,7 @@ pub fn draw_abilities(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
     let book_x = MAP_SCREEN_X + 1;
     let book_y = MAP_SCREEN_Y + 1;
     let box_w = 25;
-    let box_h = gs.player_abilities.len() * 2 + 1;
+    let box_h = gs.player_weapon.abilities.len() * 2 + 1;
 
     ctx.draw_box(
         book_x,
@@ -19,7 +19,7 @@ pub fn draw_abilities(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
     let header = "Use Ability";
     ctx.print(book_x + 1, book_y, header);
 
-    for (i, ability) in gs.player_abilities.iter().enumerate() {
+    for (i, ability) in gs.player_weapon.abilities.iter().enumerate() {
         let row = book_y + 2 + 2 * i as i32;
         let mut text_color = crate::text_color();
 
@@ -16,14 +16,7 @@ pub fn update_controls_text(ecs: &World, ctx: &mut Rltk, status: &RunState) {
     let bg_color = bg_color();
     let inactive_color = text_inactive_color();
 
-    let is_reaction = {
-        let can_act = ecs.read_storage::<CanActFlag>();
-        let player = ecs.fetch::<Entity>();
-        match can_act.get(*player) {
-            None => false,
-            Some(player_can_act) => player_can_act.is_reaction,
-        }
-    };
+    let is_reaction = crate::player::is_reacting(ecs);
 
     match *status {
         RunState::AwaitingInput => {
@@ -45,6 +38,14 @@ pub fn update_controls_text(ecs: &World, ctx: &mut Rltk, status: &RunState) {
 
             ctx.print_color(space_section_x, y, icon_color, bg_color, "[SPACE]");
             ctx.print(space_section_x + 8, y, space_action_str);
+
+            let guard_section_x = 28;
+            ctx.print_color(guard_section_x, y, icon_color, bg_color, "g");
+            ctx.print(guard_section_x + 1, y, "uard");
+
+            let danger_section_x = 35;
+            ctx.print_color(danger_section_x, y, icon_color, bg_color, "d");
+            ctx.print(danger_section_x + 1, y, "anger");
         }
         RunState::Targetting { validity_mode, .. } => {
             // movement controls
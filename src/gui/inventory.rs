@@ -4,7 +4,7 @@ use crate::*;
 pub fn draw_inventory(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
     let book_x = MAP_SCREEN_X + 1;
     let book_y = MAP_SCREEN_Y + 1;
-    let box_w = 15;
+    let box_w = 20;
     let box_h = gs.player_inventory.consumables.len() * 2 + 1;
 
     ctx.draw_box(
@@ -16,7 +16,11 @@ pub fn draw_inventory(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
         RGB::named(rltk::BLACK),
     );
 
-    let header = "Use Item";
+    let header = format!(
+        "Use Item ({}/{})",
+        gs.player_inventory.consumables.len(),
+        inventory::INVENTORY_SLOTS
+    );
     ctx.print(book_x + 1, book_y, header);
 
     for (i, item) in gs.player_inventory.consumables.iter().enumerate() {
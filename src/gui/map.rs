@@ -1,13 +1,21 @@
 use super::consts::*;
 use crate::*;
+use std::collections::HashMap;
 
-pub fn draw_all(ecs: &World, ctx: &mut Rltk) {
+// this many attacks landing on the same tile is drawn at full danger
+const DANGER_STACK_MAX: u32 = 3;
+
+pub fn draw_all(ecs: &World, ctx: &mut Rltk, show_danger: bool) {
     // map elements
     draw_map(ecs, ctx);
     draw_renderables(ecs, ctx);
     draw_particles(ecs, ctx);
     // draw_blocked_tiles(ecs, ctx);
     draw_attacks_in_progress(ecs, ctx);
+
+    if show_danger {
+        draw_danger_overlay(ecs, ctx);
+    }
 }
 
 pub fn draw_map(ecs: &World, ctx: &mut Rltk) {
@@ -37,6 +45,11 @@ pub fn draw_map(ecs: &World, ctx: &mut Rltk) {
                 TileType::ShallowWater => (rltk::to_cp437('~'), map_shallow_water_color()),
                 TileType::DownStairs => (rltk::to_cp437('>'), map_exit_color()),
                 TileType::NewLevel => (rltk::to_cp437('>'), map_exit_color()),
+                TileType::Lava => (rltk::to_cp437('~'), map_lava_color()),
+                TileType::Spikes => (rltk::to_cp437('^'), map_spikes_color()),
+                TileType::Ice => (rltk::to_cp437('.'), map_ice_color()),
+                TileType::Grass => (rltk::to_cp437('"'), map_grass_color()),
+                TileType::BurningGrass => (rltk::to_cp437('^'), map_fire_color()),
             };
 
             if !map.visible_tiles[idx] && !SHOW_MAP {
@@ -209,7 +222,7 @@ pub fn draw_attacks_in_progress(ecs: &World, ctx: &mut Rltk) {
 
     ctx.set_active_console(0);
     for (attack, _) in (&attacks, &in_progress).join() {
-        for point in attack_type::each_attack_target(attack.main, attack.loc) {
+        for point in attack_type::each_intent_target(attack) {
             if !map.camera.on_screen(point) {
                 continue;
             }
@@ -265,6 +278,187 @@ pub fn draw_attacks_in_progress(ecs: &World, ctx: &mut Rltk) {
     ctx.set_active_console(1);
 }
 
+// how many attacks are headed for a tile, and when the first of them gets there
+struct Threat {
+    count: u32,
+    soonest: u32,
+}
+
+// Every tile an enemy attack will hit, with the number of ticks until it lands. Projectiles that
+// haven't been fired yet are traced along the line they will be fired on
+fn get_threats(ecs: &World) -> HashMap<usize, Threat> {
+    let entities = ecs.entities();
+    let attacks = ecs.read_storage::<AttackIntent>();
+    let frames = ecs.read_storage::<FrameData>();
+    let positions = ecs.read_storage::<Position>();
+    let attack_paths = ecs.read_storage::<AttackPath>();
+    let map = ecs.fetch::<Map>();
+    let player = ecs.fetch::<Entity>();
+
+    let mut threats: HashMap<usize, Threat> = HashMap::new();
+
+    for (ent, intent, frame) in (&entities, &attacks, &frames).join() {
+        if ent == *player || frame.cancelled || frame.current >= frame.startup + frame.active {
+            continue;
+        }
+
+        // active attacks hit again every tick until they finish
+        let lands_in = std::cmp::max(1, (frame.startup + 1).saturating_sub(frame.current));
+        let path_trait = attack_type::get_attack_traits(intent.main)
+            .into_iter()
+            .find_map(|tr| match tr {
+                AttackTrait::FollowsPath { step_delay, on_hit } => Some((step_delay, on_hit)),
+                _ => None,
+            });
+
+        let hits = match path_trait {
+            None => attack_type::each_intent_target(intent)
+                .into_iter()
+                .map(|point| (point, lands_in))
+                .collect(),
+            Some((step_delay, on_hit)) => {
+                // once fired, the projectile is drawn from its AttackPath instead
+                if frame.current > frame.startup {
+                    continue;
+                }
+
+                let pos = match positions.get(ent) {
+                    None => continue,
+                    Some(pos) => pos.as_point(),
+                };
+                let mut path = rltk::line2d(rltk::LineAlg::Bresenham, intent.loc, pos);
+                path.pop();
+                path.reverse();
+
+                // the projectile takes its first step on the tick it is fired
+                project_path(&map, &path, 0, on_hit, intent.bonus, |idx| {
+                    std::cmp::max(lands_in, lands_in - 1 + idx as u32 * step_delay)
+                })
+            }
+        };
+
+        add_threats(&mut threats, &map, hits);
+    }
+
+    for attack_path in (&attack_paths).join() {
+        let hits = project_path(
+            &map,
+            &attack_path.path,
+            attack_path.index + 1,
+            attack_path.on_hit,
+            attack_path.bonus,
+            |idx| (idx - attack_path.index) as u32 * attack_path.step_delay - attack_path.cur_delay,
+        );
+
+        add_threats(&mut threats, &map, hits);
+    }
+
+    threats
+}
+
+pub fn draw_danger_overlay(ecs: &World, ctx: &mut Rltk) {
+    let threats = get_threats(ecs);
+    let map = ecs.fetch::<Map>();
+
+    for (index, threat) in threats.iter() {
+        let point = map.index_to_point2d(*index);
+        if !map.camera.on_screen(point) || !map.visible_tiles[*index] {
+            continue;
+        }
+
+        let stacked = std::cmp::min(threat.count - 1, DANGER_STACK_MAX - 1) as f32
+            / (DANGER_STACK_MAX - 1) as f32;
+        ctx.set_active_console(0);
+        highlight_bg(
+            ctx,
+            &map.camera.origin,
+            &point,
+            crate::attack_intent_color().lerp(crate::danger_stack_color(), stacked),
+        );
+
+        // leave creatures visible, the highlight under them is enough
+        if map.creature_map.contains_key(index) {
+            continue;
+        }
+
+        let symbol = match char::from_digit(threat.soonest, 10) {
+            Some(digit) => rltk::to_cp437(digit),
+            None => rltk::to_cp437('+'),
+        };
+
+        ctx.set_active_console(1);
+        set_map_tile(
+            ctx,
+            &map.camera.origin,
+            &point,
+            crate::danger_countdown_color(),
+            symbol,
+        );
+    }
+    ctx.set_active_console(1);
+}
+
+// the tiles a projectile crosses from a point on its path, up to whatever stops it, and the area
+// it hits when it gets there
+fn project_path(
+    map: &Map,
+    path: &[rltk::Point],
+    start: usize,
+    on_hit: AttackType,
+    bonus: AttackBonus,
+    eta: impl Fn(usize) -> u32,
+) -> Vec<(rltk::Point, u32)> {
+    let mut hits = Vec::new();
+    let mut end = None;
+
+    for (idx, point) in path.iter().enumerate().skip(start) {
+        hits.push((*point, eta(idx)));
+        end = Some((*point, eta(idx)));
+
+        if !map.is_tile_valid(point.x, point.y) {
+            break;
+        }
+    }
+
+    if let Some((loc, lands_in)) = end {
+        let intent = AttackIntent {
+            main: on_hit,
+            loc,
+            bonus,
+        };
+
+        for point in attack_type::each_intent_target(&intent) {
+            hits.push((point, lands_in));
+        }
+    }
+
+    hits
+}
+
+// an attack that covers a tile more than once still only counts once there
+fn add_threats(threats: &mut HashMap<usize, Threat>, map: &Map, hits: Vec<(rltk::Point, u32)>) {
+    let mut soonest: HashMap<usize, u32> = HashMap::new();
+    for (point, lands_in) in hits {
+        if !map.in_bounds(point) {
+            continue;
+        }
+
+        let entry = soonest
+            .entry(map.point2d_to_index(point))
+            .or_insert(lands_in);
+        *entry = std::cmp::min(*entry, lands_in);
+    }
+
+    for (index, lands_in) in soonest {
+        let threat = threats.entry(index).or_insert(Threat {
+            count: 0,
+            soonest: lands_in,
+        });
+        threat.count += 1;
+        threat.soonest = std::cmp::min(threat.soonest, lands_in);
+    }
+}
+
 fn set_map_tile(
     ctx: &mut Rltk,
     camera_pos: &rltk::Point,
@@ -323,6 +517,9 @@ pub fn draw_viewable_info(ecs: &World, ctx: &mut Rltk, entity: &Entity, index: u
     let healths = ecs.read_storage::<Health>();
     let atk_in_progress = ecs.read_storage::<AttackInProgress>();
     let blocking = ecs.read_storage::<BlockAttack>();
+    let statuses = ecs.read_storage::<StatusEffects>();
+    let multitiles = ecs.read_storage::<MultiTile>();
+    let ai_states = ecs.read_storage::<AiState>();
     let map = ecs.fetch::<Map>();
 
     let pos = positions
@@ -350,14 +547,42 @@ pub fn draw_viewable_info(ecs: &World, ctx: &mut Rltk, entity: &Entity, index: u
         format!("HP: {}/{}", health.current, health.max),
     );
 
+    if let Some(effects) = statuses.get(*entity) {
+        crate::status::draw_status_icons(ctx, box_x + 1, box_y + 2, effects);
+    }
+
     if atk_in_progress.get(*entity).is_some() {
         ctx.print(box_x + 1, box_y + 3, "Attacking");
     } else if blocking.get(*entity).is_some() {
         ctx.print(box_x + 1, box_y + 3, "Blocking");
+    } else if let Some(state) = ai_states.get(*entity) {
+        let status = match state.status {
+            Behavior::Sleep => "Asleep",
+            Behavior::Flee { .. } => "Fleeing",
+            _ => "Idle",
+        };
+        ctx.print(box_x + 1, box_y + 3, status);
     } else {
         ctx.print(box_x + 1, box_y + 3, "Idle");
     }
 
+    // bosses show how close each part is to breaking instead
+    if let Some(multi) = multitiles.get(*entity) {
+        for (i, part) in multi.part_list.iter().enumerate() {
+            ctx.print(
+                box_x + 1,
+                box_y + 5 + i as i32,
+                format!(
+                    "{} {}/{}",
+                    part.name,
+                    std::cmp::max(part.health, 0),
+                    part.max_health
+                ),
+            );
+        }
+        return;
+    }
+
     for (i, line) in view.description.iter().enumerate() {
         ctx.print(box_x + 1, box_y + 5 + i as i32, line.clone());
     }
@@ -8,3 +8,4 @@ pub mod map;
 pub mod overworld;
 pub mod sidebar;
 pub mod tooltip;
+pub mod weapon_select;
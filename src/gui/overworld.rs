@@ -1,10 +1,140 @@
-use rltk::{Rltk, RGB};
+use super::consts::*;
+use crate::*;
+use crate::{shop, upgrade};
 
-pub fn draw_upgrades(ctx: &mut Rltk) {
-    let book_x = 8;
-    let book_y = 6;
+pub fn draw_upgrades(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
+    let upgrades = upgrade::get_upgrades(&gs.player_weapon);
+    let book_x = MAP_SCREEN_X + 1;
+    let book_y = MAP_SCREEN_Y + 1;
+    let box_w = 44;
+    let box_h = upgrades.len() as i32 + 5;
+
+    ctx.draw_box(
+        book_x,
+        book_y,
+        box_w,
+        box_h,
+        RGB::named(rltk::GREY),
+        RGB::named(rltk::BLACK),
+    );
+
+    let header = "Blacksmith";
+    ctx.print(book_x + 1, book_y, header);
+
+    let money = format!("${}", gs.player_inventory.money);
+    ctx.print(book_x + box_w - money.len() as i32, book_y, money);
+
+    for (i, upgrade) in upgrades.iter().enumerate() {
+        let row = book_y + 2 + i as i32;
+        let mut text_color = crate::text_color();
+
+        if i == selected_idx {
+            ctx.set_active_console(0);
+            for dx in 0..box_w - 1 {
+                ctx.set_bg(book_x + 1 + dx, row, crate::select_highlight_color());
+            }
+            ctx.set_active_console(1);
+
+            text_color = crate::select_text_color();
+        }
+
+        let cdx = char::from_u32(i as u32 + 97).unwrap();
+        ctx.print_color(book_x + 1, row, text_color, bg_color(), cdx);
+        ctx.print_color(book_x + 2, row, text_color, bg_color(), '-');
+        ctx.print_color(
+            book_x + 3,
+            row,
+            text_color,
+            bg_color(),
+            upgrade::get_name(gs, *upgrade),
+        );
+
+        let level = upgrade::get_level(gs, *upgrade);
+        let max_level = upgrade::get_max_level(gs, *upgrade);
+        ctx.print_color(
+            book_x + 32,
+            row,
+            text_color,
+            bg_color(),
+            format!("{}/{}", level, max_level),
+        );
+
+        let cost = if level >= max_level {
+            "max".to_string()
+        } else {
+            format!("${}", upgrade::get_cost(gs, *upgrade))
+        };
+        ctx.print_color(book_x + 37, row, text_color, bg_color(), cost);
+    }
+
+    if let Some(upgrade) = upgrades.get(selected_idx) {
+        ctx.print(
+            book_x + 1,
+            book_y + box_h - 1,
+            upgrade::get_description(*upgrade),
+        );
+    }
+}
+
+pub fn draw_missions(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
+    let offers = crate::mission_info::CONTRACT_OFFERS;
+    let next_contract = gs.ecs.fetch::<MissionInfo>().next_contract;
+    let book_x = MAP_SCREEN_X + 1;
+    let book_y = MAP_SCREEN_Y + 1;
+    let box_w = 40;
+    let box_h = offers.len() as i32 + 5;
+
+    ctx.draw_box(
+        book_x,
+        book_y,
+        box_w,
+        box_h,
+        RGB::named(rltk::GREY),
+        RGB::named(rltk::BLACK),
+    );
+
+    let header = "Contracts";
+    ctx.print(book_x + 1, book_y, header);
+
+    for (i, contract) in offers.iter().enumerate() {
+        let row = book_y + 2 + i as i32;
+        let mut text_color = crate::text_color();
+
+        if i == selected_idx {
+            ctx.set_active_console(0);
+            for dx in 0..box_w - 1 {
+                ctx.set_bg(book_x + 1 + dx, row, crate::select_highlight_color());
+            }
+            ctx.set_active_console(1);
+
+            text_color = crate::select_text_color();
+        }
+
+        let cdx = char::from_u32(i as u32 + 97).unwrap();
+        ctx.print_color(book_x + 1, row, text_color, bg_color(), cdx);
+        ctx.print_color(book_x + 2, row, text_color, bg_color(), '-');
+        ctx.print_color(book_x + 3, row, text_color, bg_color(), contract.name());
+
+        // the reward scales with the arena, so show it per level
+        let reward = if next_contract == Some(*contract) {
+            "taken".to_string()
+        } else {
+            format!("${}/lv", contract.reward())
+        };
+        ctx.print_color(book_x + 32, row, text_color, bg_color(), reward);
+    }
+
+    if let Some(contract) = offers.get(selected_idx) {
+        ctx.print(book_x + 1, book_y + box_h - 1, contract.description());
+    }
+}
+
+pub fn draw_tiers(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
+    let tiers = gs.max_cleared_level as usize + 1;
+    let book_x = MAP_SCREEN_X + 1;
+    let book_y = MAP_SCREEN_Y + 1;
     let box_w = 40;
-    let box_h = 40;
+    let box_h = tiers as i32 + 5;
 
     ctx.draw_box(
         book_x,
@@ -15,15 +145,55 @@ pub fn draw_upgrades(ctx: &mut Rltk) {
         RGB::named(rltk::BLACK),
     );
 
-    let header = "Placeholder";
-    ctx.print(book_x + (box_w - header.len()) / 2, book_y + 2, header);
+    let header = "Gate";
+    ctx.print(book_x + 1, book_y, header);
+
+    for i in 0..tiers {
+        let row = book_y + 2 + i as i32;
+        let mut text_color = crate::text_color();
+
+        if i == selected_idx {
+            ctx.set_active_console(0);
+            for dx in 0..box_w - 1 {
+                ctx.set_bg(book_x + 1 + dx, row, crate::select_highlight_color());
+            }
+            ctx.set_active_console(1);
+
+            text_color = crate::select_text_color();
+        }
+
+        let cdx = char::from_u32(i as u32 + 97).unwrap();
+        ctx.print_color(book_x + 1, row, text_color, bg_color(), cdx);
+        ctx.print_color(book_x + 2, row, text_color, bg_color(), '-');
+        ctx.print_color(
+            book_x + 3,
+            row,
+            text_color,
+            bg_color(),
+            format!("Arena {}", i + 1),
+        );
+
+        let status = if i < gs.max_cleared_level as usize {
+            "cleared"
+        } else {
+            "new"
+        };
+        ctx.print_color(book_x + 32, row, text_color, bg_color(), status);
+    }
+
+    ctx.print(
+        book_x + 1,
+        book_y + box_h - 1,
+        "Pick an arena to start the descent from",
+    );
 }
 
-pub fn draw_shop(ctx: &mut Rltk) {
-    let book_x = 8;
-    let book_y = 6;
+pub fn draw_shop(gs: &State, ctx: &mut Rltk, selected_idx: usize) {
+    let stock = shop::get_stock();
+    let book_x = MAP_SCREEN_X + 1;
+    let book_y = MAP_SCREEN_Y + 1;
     let box_w = 40;
-    let box_h = 40;
+    let box_h = stock.len() as i32 + 5;
 
     ctx.draw_box(
         book_x,
@@ -34,6 +204,40 @@ pub fn draw_shop(ctx: &mut Rltk) {
         RGB::named(rltk::BLACK),
     );
 
-    let header = "Placeholder";
-    ctx.print(book_x + (box_w - header.len()) / 2, book_y + 2, header);
+    let header = "Shop";
+    ctx.print(book_x + 1, book_y, header);
+
+    let money = format!("${}", gs.player_inventory.money);
+    ctx.print(book_x + box_w - money.len() as i32, book_y, money);
+
+    for (i, item) in stock.iter().enumerate() {
+        let row = book_y + 2 + i as i32;
+        let mut text_color = crate::text_color();
+
+        if i == selected_idx {
+            ctx.set_active_console(0);
+            for dx in 0..box_w - 1 {
+                ctx.set_bg(book_x + 1 + dx, row, crate::select_highlight_color());
+            }
+            ctx.set_active_console(1);
+
+            text_color = crate::select_text_color();
+        }
+
+        let cdx = char::from_u32(i as u32 + 97).unwrap();
+        ctx.print_color(book_x + 1, row, text_color, bg_color(), cdx);
+        ctx.print_color(book_x + 2, row, text_color, bg_color(), '-');
+        ctx.print_color(book_x + 3, row, text_color, bg_color(), item.name.clone());
+        ctx.print_color(
+            book_x + 34,
+            row,
+            text_color,
+            bg_color(),
+            format!("${}", item.price),
+        );
+    }
+
+    if let Some(item) = stock.get(selected_idx) {
+        ctx.print(book_x + 1, book_y + box_h - 1, item.description.clone());
+    }
 }
@@ -13,10 +13,11 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
     let movesets = gs.ecs.read_storage::<Moveset>();
     let frames = gs.ecs.read_storage::<FrameData>();
     let schedulables = gs.ecs.read_storage::<Schedulable>();
+    let statuses = gs.ecs.read_storage::<StatusEffects>();
 
     let map = gs.ecs.fetch::<Map>();
     let player = gs.ecs.fetch::<Entity>();
-    // let m_info = gs.ecs.fetch::<MissionInfo>();
+    let m_info = gs.ecs.fetch::<MissionInfo>();
     // let next_state = gs.ecs.fetch::<RunState>();
 
     let mouse_point = ctx.mouse_point();
@@ -34,7 +35,7 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
     let mut x = SIDE_X + 1;
     let mut y = SIDE_Y + 1;
 
-    for (_, rend, view, pos, stamina, health, frame) in (
+    for (_, rend, view, pos, stamina, health, frame, effects) in (
         &players,
         &rends,
         &viewables,
@@ -42,9 +43,14 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
         &stams,
         &healths,
         (&frames).maybe(),
+        (&statuses).maybe(),
     )
         .join()
     {
+        if let Some(effects) = effects {
+            draw_status_icons(ctx, y, effects);
+        }
+
         ctx.set(x, y, rend.fg, rend.bg, rend.symbol);
         if adjusted_point.x == pos.x && adjusted_point.y == pos.y {
             ctx.print_color(
@@ -86,13 +92,14 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
     }
 
     y += 2;
-    for (rend, view, pos, health, frame, _) in (
+    for (rend, view, pos, health, frame, _, effects) in (
         &rends,
         &viewables,
         &positions,
         (&healths).maybe(),
         (&frames).maybe(),
         &movesets,
+        (&statuses).maybe(),
     )
         .join()
     {
@@ -101,6 +108,9 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
         }
 
         y += 3;
+        if let Some(effects) = effects {
+            draw_status_icons(ctx, y, effects);
+        }
         ctx.set(x, y, rend.fg, rend.bg, rend.symbol);
         if adjusted_point.x == pos.x && adjusted_point.y == pos.y {
             ctx.print_color(
@@ -133,9 +143,68 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
         }
     }
 
+    // before a run, show the contract that was taken instead
+    let objective = if map.level == 0 {
+        m_info
+            .next_contract
+            .map(|contract| format!("Next: {}", contract.name()))
+    } else {
+        m_info.objective()
+    };
+
+    if let Some(objective) = objective {
+        ctx.print(SIDE_X + 1, SIDE_H - 10, "Contract");
+        ctx.print_color(
+            SIDE_X + 1,
+            SIDE_H - 9,
+            text_highlight_color(),
+            bg_color(),
+            objective,
+        );
+    }
+
+    ctx.print(SIDE_X + 1, SIDE_H - 7, "Money");
+    ctx.print_color(
+        SIDE_X + 1,
+        SIDE_H - 6,
+        text_highlight_color(),
+        bg_color(),
+        format!("${}", gs.player_inventory.money),
+    );
+
+    ctx.print(SIDE_X + 1, SIDE_H - 4, "Weapon");
+    ctx.print_color(
+        SIDE_X + 1,
+        SIDE_H - 3,
+        text_highlight_color(),
+        bg_color(),
+        gs.player_weapon.name.clone(),
+    );
+
+    let seed = gs.ecs.fetch::<RunSeed>();
+    ctx.print(SIDE_X + 1, SIDE_H - 1, "Seed");
+    ctx.print_color(
+        SIDE_X + 1,
+        SIDE_H,
+        text_highlight_color(),
+        bg_color(),
+        seed.seed.to_string(),
+    );
+
     // Controls
     x = SIDE_W + 2;
     y = SIDE_H + 1;
+
+    if gs.playback.is_some() {
+        ctx.print_color(x, y, text_highlight_color(), bg_color(), "[+/-]");
+        ctx.print(x + 6, y, "replay speed");
+
+        x += 20;
+        ctx.print_color(x, y, text_highlight_color(), bg_color(), "[ESC]");
+        ctx.print(x + 6, y, "take over");
+        return;
+    }
+
     // ctx.print(x, y, "Controls");
     draw_movement_controls(ctx, x, y, text_highlight_color(), bg_color(), false);
 
@@ -147,14 +216,28 @@ pub fn draw_sidebar(gs: &State, ctx: &mut Rltk) {
     ctx.print_color(x, y, text_highlight_color(), bg_color(), 'a');
     ctx.print(x + 1, y, "bility");
 
-    // x += 9;
-    // ctx.print_color(x, y, text_highlight_color(), bg_color(), 'i');
-    // ctx.print(x + 1, y, "nventory");
-
     x += 9;
+    ctx.print_color(x, y, text_highlight_color(), bg_color(), 'i');
+    ctx.print(x + 1, y, "nventory");
+
+    x += 11;
+    if gs.ecs.fetch::<Map>().level == 0 {
+        ctx.print_color(x, y, text_highlight_color(), bg_color(), 'w');
+        ctx.print(x + 1, y, "eapon");
+        x += 8;
+    }
+
     ctx.print_color(x, y, text_highlight_color(), bg_color(), 'v');
     ctx.print(x + 1, y, "iew log");
 
+    x += 9;
+    ctx.print_color(x, y, text_highlight_color(), bg_color(), 's');
+    ctx.print(x + 1, y, "ave");
+
+    x += 6;
+    ctx.print_color(x, y, text_highlight_color(), bg_color(), 'r');
+    ctx.print(x + 1, y, "eplay");
+
     // super::tooltip::draw_tooltips(&gs.ecs, ctx);
 }
 
@@ -189,3 +272,9 @@ fn draw_movement_controls(ctx: &mut Rltk, x: i32, y: i32, fg: RGB, bg: RGB, inac
         ctx.print(x + 5, y, "move");
     }
 }
+
+// statuses line up against the right edge, on the same row as the name
+fn draw_status_icons(ctx: &mut Rltk, y: i32, effects: &StatusEffects) {
+    let x = SIDE_X + SIDE_W - effects.effects.len() as i32;
+    crate::status::draw_status_icons(ctx, x, y, effects);
+}
@@ -0,0 +1,474 @@
+use crate::player::PlayerCommand;
+use crate::replay::{Playback, Replay};
+use crate::weapon::WeaponType;
+use crate::*;
+
+// particles are aged as if the game was running at 60 fps
+const FRAME_TIME_MS: f32 = 1000.0 / 60.0;
+const DEFAULT_MAX_TICKS: i32 = 100_000;
+const BENCH_TICKS: i32 = 2_000;
+
+pub enum CommandSource {
+    Script(String),
+    Replay(String),
+    // the player just waits while this many monsters close in
+    Bench(u32),
+}
+
+pub struct HeadlessConfig {
+    pub source: CommandSource,
+    pub runs: u32,
+    pub level: u32,
+    pub max_ticks: i32,
+    pub weapon: Option<WeaponType>,
+}
+
+impl HeadlessConfig {
+    // returns None if the game should open a window as usual
+    pub fn from_args(args: &[String]) -> Result<Option<Self>, String> {
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+
+        let script_path = arg_value(args, "--headless").filter(|value| !value.starts_with("--"));
+        let source = match (arg_value(args, "--replay"), script_path) {
+            (Some(replay_path), _) => CommandSource::Replay(replay_path),
+            (None, _) if args.iter().any(|arg| arg == "--bench") => {
+                CommandSource::Bench(parse_arg(args, "--bench", 50)?)
+            }
+            (None, Some(script_path)) => CommandSource::Script(script_path),
+            (None, None) => {
+                return Err("--headless needs a script, a --replay file or --bench".to_string())
+            }
+        };
+
+        let weapon = match arg_value(args, "--weapon") {
+            None => None,
+            Some(name) => Some(
+                WeaponType::from_name(&name).ok_or_else(|| format!("Unknown weapon {}", name))?,
+            ),
+        };
+
+        Ok(Some(Self {
+            source,
+            runs: parse_arg(args, "--runs", 1)?,
+            level: parse_arg(args, "--level", 1)?,
+            max_ticks: parse_arg(args, "--max-ticks", DEFAULT_MAX_TICKS)?,
+            weapon,
+        }))
+    }
+}
+
+fn arg_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[String], flag: &str, default: T) -> Result<T, String> {
+    match arg_value(args, flag) {
+        None => Ok(default),
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("Invalid value {} for {}", value, flag)),
+    }
+}
+
+// Script format: one command per line, blank lines and lines starting with # are skipped
+//   n | e | s | w [count]       move in a direction
+//   wait [count]                pass the turn
+//   guard                       raise a guard, waiting afterwards keeps it up
+//   ability <index> [<x> <y>]   use an ability, with a target if it needs one
+//   item <index> [<x> <y>]      use a carried item, with a target if it needs one
+//   enter <level>               start a run from an unlocked arena tier at the gate
+pub fn parse_script(text: &str) -> Result<Vec<PlayerCommand>, String> {
+    let mut commands = Vec::new();
+
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let error = |msg: &str| format!("line {}: {} ({})", line_no + 1, msg, line);
+        let number = |index: usize| -> Result<i32, String> {
+            tokens[index]
+                .parse::<i32>()
+                .map_err(|_| error("expected a number"))
+        };
+        let count = if tokens.len() > 1 { number(1)? } else { 1 };
+
+        let command = match tokens[0] {
+            "n" => PlayerCommand::Move { dir: Direction::N },
+            "e" => PlayerCommand::Move { dir: Direction::E },
+            "s" => PlayerCommand::Move { dir: Direction::S },
+            "w" => PlayerCommand::Move { dir: Direction::W },
+            "wait" => PlayerCommand::Wait,
+            "guard" => PlayerCommand::Guard,
+            "enter" if tokens.len() == 2 => PlayerCommand::EnterArena {
+                level: number(1)? as u32,
+            },
+            "ability" | "item" => {
+                let index = match tokens.len() {
+                    2 | 4 => number(1)? as usize,
+                    _ => return Err(error("expected <index> [<x> <y>]")),
+                };
+                let target = if tokens.len() == 4 {
+                    Some(rltk::Point::new(number(2)?, number(3)?))
+                } else {
+                    None
+                };
+
+                if tokens[0] == "ability" {
+                    commands.push(PlayerCommand::Ability { index, target });
+                } else {
+                    commands.push(PlayerCommand::UseItem { index, target });
+                }
+                continue;
+            }
+            _ => return Err(error("unknown command")),
+        };
+
+        for _ in 0..count {
+            commands.push(command);
+        }
+    }
+
+    Ok(commands)
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Cleared,
+    KnockedOut,
+    OutOfCommands,
+    OutOfTime,
+}
+
+pub struct RunSummary {
+    pub outcome: Outcome,
+    pub ticks: i32,
+    pub health: i32,
+    pub remaining_enemies: i32,
+    pub money: u32,
+    pub desync: Option<String>,
+}
+
+pub fn run(config: HeadlessConfig, seed: RunSeed) -> rltk::BError {
+    match &config.source {
+        CommandSource::Script(path) => run_script(&config, path, seed),
+        CommandSource::Replay(path) => run_replay(&config, path),
+        CommandSource::Bench(enemies) => run_bench(&config, *enemies, seed),
+    }
+}
+
+// each run uses the next seed after the previous one, so a batch is reproducible from its first seed
+fn run_script(config: &HeadlessConfig, path: &str, seed: RunSeed) -> rltk::BError {
+    let text = std::fs::read_to_string(path)?;
+    let commands = parse_script(&text)?;
+
+    for run_index in 0..config.runs {
+        let run_seed = RunSeed::new(seed.seed.wrapping_add(run_index as u64));
+        let playback = Playback::from_commands(&commands);
+        let summary = simulate(
+            playback,
+            run_seed,
+            Some(config.level),
+            config.weapon,
+            0,
+            config.max_ticks,
+        );
+        print_summary(run_index + 1, run_seed, &summary);
+    }
+
+    Ok(())
+}
+
+// replays start from the overworld like the run they were recorded from, and fail on a desync
+fn run_replay(config: &HeadlessConfig, path: &str) -> rltk::BError {
+    let replay = Replay::read(path)?;
+    let playback = Playback::from_replay(&replay);
+    let summary = simulate(
+        playback,
+        replay.seed,
+        None,
+        None,
+        replay.max_cleared_level,
+        config.max_ticks,
+    );
+    print_summary(1, replay.seed, &summary);
+
+    match summary.desync {
+        None => Ok(()),
+        Some(desync) => Err(desync.into()),
+    }
+}
+
+// Times a crowd of monsters hunting a player who never fights back, on a full size arena of the
+// given level. Everyone starts out chasing, so every monster is pathing every turn
+fn run_bench(config: &HeadlessConfig, enemies: u32, seed: RunSeed) -> rltk::BError {
+    let mut gs = State::new();
+    gs.new_game(seed);
+    gs.new_level(config.level, None);
+    let enemies = fill_bench_arena(&mut gs, enemies);
+
+    let max_ticks = std::cmp::min(config.max_ticks, BENCH_TICKS);
+    let start = std::time::Instant::now();
+
+    while gs.tick < max_ticks {
+        let status = *gs.ecs.fetch::<RunState>();
+        let next_status = match status {
+            RunState::AwaitingInput => match player::start_turn(&mut gs) {
+                Some(start_status) => start_status,
+                None => {
+                    let status = player::apply_command(&mut gs, PlayerCommand::Wait);
+                    if status == RunState::Running {
+                        gs.end_player_turn();
+                    }
+                    status
+                }
+            },
+            RunState::Running => {
+                gs.run_systems();
+                *gs.ecs.fetch::<RunState>()
+            }
+            RunState::Dead { .. } => break,
+            _ => RunState::Running,
+        };
+
+        sys_particle::expire_particles(&mut gs.ecs, FRAME_TIME_MS);
+        *gs.ecs.write_resource::<RunState>() = next_status;
+    }
+
+    let elapsed = start.elapsed().as_secs_f32() * 1000.0;
+    let map = gs.ecs.fetch::<Map>();
+    println!(
+        "{} monsters on a {}x{} map: {} ticks in {:.0}ms, {:.3}ms per tick",
+        enemies,
+        map.width,
+        map.height,
+        gs.tick,
+        elapsed,
+        elapsed / std::cmp::max(gs.tick, 1) as f32
+    );
+
+    Ok(())
+}
+
+// Tops the level up to the given number of monsters, all awake and heading for the player. Returns
+// how many there are, which is fewer than asked for if the floor runs out
+fn fill_bench_arena(gs: &mut State, enemies: u32) -> u32 {
+    let player = *gs.ecs.fetch::<Entity>();
+    let player_point = gs
+        .ecs
+        .read_storage::<Position>()
+        .get(player)
+        .expect("player didn't have a position")
+        .as_point();
+
+    // enough health to outlast the whole crowd
+    if let Some(health) = gs.ecs.write_storage::<Health>().get_mut(player) {
+        health.max = 1_000_000;
+        health.current = health.max;
+    }
+
+    let monsters: Vec<_> = spawn::spawner::MONSTERS.values().cloned().collect();
+    let mut count = (&gs.ecs.read_storage::<AiState>()).join().count() as u32;
+    let mut rng = rltk::RandomNumberGenerator::seeded(gs.ecs.fetch::<RunSeed>().seed);
+
+    let mut open: Vec<usize> = {
+        let map = gs.ecs.fetch::<Map>();
+        (0..map.tiles.len())
+            .filter(|index| {
+                map.tiles[*index] == TileType::Floor
+                    && !map.blocked_tiles[*index]
+                    && !map.creature_map.contains_key(index)
+            })
+            .collect()
+    };
+
+    // shuffle, so the crowd is spread over the whole arena
+    for i in (1..open.len()).rev() {
+        let j = rng.range(0, i + 1);
+        open.swap(i, j);
+    }
+
+    let wanted = enemies.saturating_sub(count) as usize;
+    if open.len() < wanted {
+        eprintln!(
+            "Only room for {} more monsters, {} asked for",
+            open.len(),
+            wanted
+        );
+    }
+
+    for index in open.into_iter().take(wanted) {
+        let point = gs.ecs.fetch::<Map>().index_to_point2d(index);
+        let info = &monsters[count as usize % monsters.len()];
+        let monster = spawn::monster::build_monster(&mut gs.ecs, info, point);
+        spawn::spawner::track_entity(&mut gs.ecs, monster, index);
+        count += 1;
+    }
+
+    for state in (&mut gs.ecs.write_storage::<AiState>()).join() {
+        state.status = Behavior::Chase {
+            target_point: player_point,
+        };
+    }
+
+    count
+}
+
+fn print_summary(run_number: u32, seed: RunSeed, summary: &RunSummary) {
+    println!(
+        "run {} (seed {}): {:?} after {} ticks, hp {}, {} enemies remaining, ${}",
+        run_number,
+        seed.seed,
+        summary.outcome,
+        summary.ticks,
+        summary.health,
+        summary.remaining_enemies,
+        summary.money
+    );
+}
+
+// Play through a run, feeding the commands in whenever the player can act. With an arena level,
+// the run starts in that arena and ends once it is cleared
+pub fn simulate(
+    mut playback: Playback,
+    seed: RunSeed,
+    arena_level: Option<u32>,
+    weapon: Option<WeaponType>,
+    max_cleared_level: u32,
+    max_ticks: i32,
+) -> RunSummary {
+    let mut gs = State::new();
+    gs.new_game(seed);
+    gs.max_cleared_level = max_cleared_level;
+    gs.saves_progress = false;
+    if let Some(weapon_type) = weapon {
+        gs.equip_weapon(weapon_type);
+    }
+    if let Some(level) = arena_level {
+        gs.ecs.insert(RunState::ChangeMap { level });
+    }
+
+    let mut outcome = Outcome::OutOfTime;
+
+    while gs.tick < max_ticks {
+        let mut next_status = *gs.ecs.fetch::<RunState>();
+
+        match next_status {
+            RunState::AwaitingInput => {
+                if arena_level.is_some() && gs.ecs.fetch::<Map>().exit_spawned {
+                    outcome = Outcome::Cleared;
+                    break;
+                }
+
+                if let Some(start_status) = player::start_turn(&mut gs) {
+                    next_status = start_status;
+                } else {
+                    let command = match playback.next_command(gs.tick) {
+                        Some(command) => command,
+                        None => {
+                            outcome = Outcome::OutOfCommands;
+                            break;
+                        }
+                    };
+
+                    next_status = player::apply_command(&mut gs, command);
+                    if next_status == RunState::Running {
+                        gs.end_player_turn();
+                    }
+                }
+            }
+            RunState::Targetting { .. } => {
+                next_status = match playback.peek() {
+                    Some(PlayerCommand::Target { .. }) => {
+                        let command = playback.next_command(gs.tick).unwrap();
+                        gs.attack_modifier = None;
+                        let status = player::apply_command(&mut gs, command);
+                        gs.end_player_turn();
+                        status
+                    }
+                    _ => RunState::AwaitingInput,
+                };
+            }
+            RunState::Charging { dir, speed } => {
+                gs.player_charging = (true, dir, speed, false);
+                next_status = RunState::Running;
+            }
+            RunState::Running => {
+                gs.run_systems();
+                next_status = *gs.ecs.fetch::<RunState>();
+            }
+            RunState::HitPause { .. } => {
+                sys_particle::ParticleSpawnSystem.run_now(&gs.ecs);
+                next_status = RunState::Running;
+            }
+            RunState::GenerateLevel => {
+                next_status = match gs.open_gate() {
+                    RunState::TierSelect { .. } => RunState::AwaitingInput,
+                    next_status => next_status,
+                };
+            }
+            RunState::ChangeMap { level } => {
+                gs.change_map(level);
+                next_status = RunState::AwaitingInput;
+            }
+            RunState::NextFloor => {
+                gs.next_floor();
+                next_status = RunState::AwaitingInput;
+            }
+            RunState::Dead { .. } => {
+                if playback.peek() != Some(PlayerCommand::Retry) {
+                    outcome = Outcome::KnockedOut;
+                    break;
+                }
+
+                let command = playback.next_command(gs.tick).unwrap();
+                next_status = player::apply_command(&mut gs, command);
+            }
+            // menus only exist for keyboard input, so there is nothing to wait on here
+            RunState::ViewEnemy { .. }
+            | RunState::AbilitySelect { .. }
+            | RunState::WeaponSelect { .. }
+            | RunState::UpgradeSelect { .. }
+            | RunState::ShopSelect { .. }
+            | RunState::MissionSelect { .. }
+            | RunState::TierSelect { .. }
+            | RunState::InventorySelect { .. }
+            | RunState::ViewGameLog => {
+                next_status = RunState::AwaitingInput;
+            }
+        }
+
+        sys_particle::expire_particles(&mut gs.ecs, FRAME_TIME_MS);
+
+        let mut status_writer = gs.ecs.write_resource::<RunState>();
+        *status_writer = next_status;
+    }
+
+    summarize(&gs, outcome, playback.desync)
+}
+
+fn summarize(gs: &State, outcome: Outcome, desync: Option<String>) -> RunSummary {
+    let player = gs.ecs.fetch::<Entity>();
+    let healths = gs.ecs.read_storage::<Health>();
+    let positions = gs.ecs.read_storage::<Position>();
+    let ais = gs.ecs.read_storage::<AiState>();
+
+    let health = healths.get(*player).map_or(0, |health| health.current);
+    let remaining_enemies = (&healths, &positions, &ais).join().count() as i32;
+
+    RunSummary {
+        outcome,
+        ticks: gs.tick,
+        health,
+        remaining_enemies,
+        money: gs.player_inventory.money,
+        desync,
+    }
+}
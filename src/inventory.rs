@@ -1,7 +1,30 @@
+use crate::weapon::WeaponType;
+use crate::AttackBonus;
+use serde::{Deserialize, Serialize};
+
+pub const INVENTORY_SLOTS: usize = 4;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Inventory {
     pub money: u32,
     pub armor_level: u32,
     pub consumables: Vec<String>,
+    pub ability_upgrades: Vec<AbilityUpgrades>,
+}
+
+// upgrades bought for one ability, kept per weapon so they survive swapping weapons
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AbilityUpgrades {
+    pub weapon_type: WeaponType,
+    pub index: usize,
+    pub bonus: AttackBonus,
+}
+
+// Money earned while the systems run. Systems can't reach the inventory, so this is collected
+// into it at the end of each tick
+#[derive(Default)]
+pub struct Earnings {
+    pub money: u32,
 }
 
 impl Inventory {
@@ -10,6 +33,37 @@ impl Inventory {
             money: 0,
             armor_level: 0,
             consumables: vec![],
+            ability_upgrades: vec![],
         }
     }
+
+    pub fn is_full(&self) -> bool {
+        self.consumables.len() >= INVENTORY_SLOTS
+    }
+
+    pub fn get_bonus(&self, weapon_type: WeaponType, index: usize) -> AttackBonus {
+        self.ability_upgrades
+            .iter()
+            .find(|upgrades| upgrades.weapon_type == weapon_type && upgrades.index == index)
+            .map(|upgrades| upgrades.bonus)
+            .unwrap_or_default()
+    }
+
+    pub fn get_bonus_mut(&mut self, weapon_type: WeaponType, index: usize) -> &mut AttackBonus {
+        let position = self
+            .ability_upgrades
+            .iter()
+            .position(|upgrades| upgrades.weapon_type == weapon_type && upgrades.index == index);
+
+        let position = position.unwrap_or_else(|| {
+            self.ability_upgrades.push(AbilityUpgrades {
+                weapon_type,
+                index,
+                bonus: AttackBonus::default(),
+            });
+            self.ability_upgrades.len() - 1
+        });
+
+        &mut self.ability_upgrades[position].bonus
+    }
 }
@@ -10,23 +10,34 @@ use crate::map_builder::MapBuilderArgs;
 use rltk::{Algorithm2D, GameState, Rltk, RGB};
 use specs::prelude::*;
 
+mod area_run;
 mod attack_type;
 mod camera;
 mod colors;
 mod components;
 mod data;
 mod direction;
+mod flow_map;
 mod gamelog;
 mod gui;
+mod headless;
 mod inventory;
 mod map;
 mod map_builder;
+mod mission_info;
 mod monster_part;
 mod player;
 mod range_type;
+mod replay;
+mod run_seed;
+mod saveload;
+mod shop;
 mod spawn;
+mod status;
+mod storage;
 mod sys_ai;
 mod sys_attack;
+mod sys_block;
 mod sys_death;
 mod sys_frame_data;
 mod sys_mapindex;
@@ -34,27 +45,32 @@ mod sys_movement;
 mod sys_partbreak;
 mod sys_particle;
 mod sys_partmove;
-mod sys_pickup;
 mod sys_projectile;
 mod sys_push;
+mod sys_reaction;
 mod sys_spawner;
 mod sys_stun;
+mod sys_terrain;
 mod sys_trap_ai;
 mod sys_turn;
 mod sys_visibility;
+mod upgrade;
 mod weapon;
 
 pub mod consts;
 
+pub use area_run::AreaRun;
 pub use attack_type::*;
 pub use camera::*;
 pub use colors::*;
 pub use components::*;
 pub use direction::Direction;
-pub use map::{Map, TileType};
+pub use map::{Hazard, Map, TileType};
+pub use mission_info::{Contract, MissionInfo};
 pub use monster_part::*;
 pub use range_type::*;
-pub use sys_ai::{Behavior, NextIntent};
+pub use run_seed::{RngStream, RunSeed};
+pub use sys_ai::{Behavior, NextIntent, Noises};
 pub use sys_particle::{ParticleBuilder, ParticleRequest};
 pub use sys_spawner::{SpawnRequest, SpawnType, Spawner};
 
@@ -73,6 +89,7 @@ pub enum RunState {
     AwaitingInput,
     Targetting {
         attack_type: AttackType,
+        source: player::AttackSource,
         cursor_point: rltk::Point,
         validity_mode: TargettingValid,
         show_path: bool,
@@ -85,9 +102,11 @@ pub enum RunState {
         remaining_time: f32,
     },
     GenerateLevel,
+    // start a descent from the given tier
     ChangeMap {
         level: u32,
     },
+    NextFloor,
     Dead {
         success: bool,
     },
@@ -98,6 +117,21 @@ pub enum RunState {
     AbilitySelect {
         index: usize,
     },
+    WeaponSelect {
+        index: usize,
+    },
+    UpgradeSelect {
+        index: usize,
+    },
+    ShopSelect {
+        index: usize,
+    },
+    MissionSelect {
+        index: usize,
+    },
+    TierSelect {
+        index: usize,
+    },
     InventorySelect {
         index: usize,
     },
@@ -112,11 +146,36 @@ pub struct State {
     attack_modifier: Option<AttackType>,
     player_inventory: inventory::Inventory,
     player_charging: (bool, crate::Direction, u8, bool),
-    player_abilities: Vec<AttackData>,
-    max_cleared_level: i32,
+    player_weapon: weapon::Weapon,
+    // highest arena tier cleared, across sessions. Every tier up to the one after it is open
+    max_cleared_level: u32,
+    recording: Option<replay::Replay>,
+    playback: Option<replay::Playback>,
+    // overlay of every tile enemy attacks are about to hit
+    show_danger: bool,
+    // replays and scripts play against the progress they were recorded with, and leave it alone
+    saves_progress: bool,
 }
 
 impl State {
+    fn new() -> Self {
+        State {
+            ecs: World::new(),
+            tick: 0,
+            tab_targets: Vec::new(),
+            tab_index: 0,
+            attack_modifier: None,
+            player_inventory: inventory::Inventory::new(),
+            player_charging: (false, crate::Direction::N, 0, false),
+            player_weapon: weapon::get_weapon(weapon::WeaponType::Lance),
+            max_cleared_level: 0,
+            recording: None,
+            playback: None,
+            show_danger: false,
+            saves_progress: true,
+        }
+    }
+
     fn register_components(&mut self) {
         self.ecs.register::<Position>();
         self.ecs.register::<Renderable>();
@@ -133,6 +192,8 @@ impl State {
 
         self.ecs.register::<Health>();
         self.ecs.register::<Stamina>();
+        self.ecs.register::<Armor>();
+        self.ecs.register::<Bounty>();
         self.ecs.register::<AttackIntent>();
         self.ecs.register::<MoveIntent>();
         self.ecs.register::<PartMoveIntent>();
@@ -142,11 +203,10 @@ impl State {
 
         self.ecs.register::<AttackInProgress>();
         self.ecs.register::<BlockAttack>();
+        self.ecs.register::<CanBlock>();
         self.ecs.register::<AiState>();
         self.ecs.register::<TrapAiState>();
 
-        self.ecs.register::<Heal>();
-        self.ecs.register::<EarthScroll>();
         self.ecs.register::<Item>();
         self.ecs.register::<Openable>();
         self.ecs.register::<Fragile>();
@@ -158,17 +218,27 @@ impl State {
         self.ecs.register::<Npc>();
         self.ecs.register::<Invulnerable>();
         self.ecs.register::<Stunned>();
+        self.ecs.register::<StatusEffects>();
         self.ecs.register::<MissionTarget>();
+        self.ecs.register::<Guardian>();
+        self.ecs.register::<Boss>();
+        self.ecs.register::<Squad>();
+
+        saveload::register(&mut self.ecs);
     }
 
-    fn new_game(&mut self) {
+    fn new_game(&mut self, seed: RunSeed) {
         self.register_components();
 
         self.ecs.insert(RunState::Running);
         self.ecs.insert(sys_particle::ParticleBuilder::new());
         self.ecs.insert(sys_spawner::Spawner::new());
+        self.ecs.insert(sys_ai::Noises::new());
+        self.ecs.insert(inventory::Earnings::default());
+        self.ecs.insert(MissionInfo::new());
+        self.ecs.insert(AreaRun::new());
 
-        let mut rng = rltk::RandomNumberGenerator::new();
+        let mut rng = seed.stream(RngStream::World);
 
         // Add a dummy map and player to the ecs
         let map = Map::new(1, 1, 0, "Dummy", "#FFFFFF", &mut rng);
@@ -176,7 +246,9 @@ impl State {
 
         let player = spawn::spawner::build_player(&mut self.ecs, rltk::Point::new(0, 0));
         self.ecs.insert(player);
+        self.recording = Some(replay::Replay::new(seed));
         self.ecs.insert(rng);
+        self.ecs.insert(seed);
 
         let log = gamelog::GameLog {
             entries: Vec::new(),
@@ -193,9 +265,11 @@ impl State {
 
         sys_trap_ai::TrapAiSystem.run_now(&self.ecs);
         sys_ai::AiSystem.run_now(&self.ecs);
+        sys_reaction::ReactionSystem.run_now(&self.ecs);
         sys_turn::TurnSystem.run_now(&self.ecs);
 
         sys_frame_data::FrameDataSystem.run_now(&self.ecs);
+        sys_block::BlockSystem.run_now(&self.ecs);
         sys_attack::AttackSystem.run_now(&self.ecs);
         sys_movement::MovementSystem.run_now(&self.ecs);
         sys_projectile::ProjectileSystem.run_now(&self.ecs);
@@ -210,22 +284,33 @@ impl State {
         // re-index because part movements may have changed blocked tiles
         sys_mapindex::MapIndexSystem.run_now(&self.ecs);
         sys_push::PushSystem.run_now(&self.ecs);
-
-        // pickups happen after movement
-        sys_pickup::PickupSystem.run_now(&self.ecs);
+        sys_terrain::TerrainSystem.run_now(&self.ecs);
 
         // death needs to run after attacks so bodies are cleaned up
         sys_death::DeathSystem.run_now(&self.ecs);
 
         sys_spawner::SpawnSystem.run_now(&self.ecs);
+        spawn::spawner::spawn_drops(&mut self.ecs);
         sys_visibility::VisibilitySystem.run_now(&self.ecs);
         sys_particle::ParticleSpawnSystem.run_now(&self.ecs);
 
         self.ecs.maintain();
+        self.collect_earnings();
+
+        // pickups happen once the player can act again, so items can go straight into the bag
+        if *self.ecs.fetch::<RunState>() == RunState::AwaitingInput {
+            player::pick_up_items(self);
+        }
 
         RunState::Running
     }
 
+    fn collect_earnings(&mut self) {
+        let mut earnings = self.ecs.fetch_mut::<inventory::Earnings>();
+        self.player_inventory.money += earnings.money;
+        earnings.money = 0;
+    }
+
     fn entities_need_cleanup(&mut self) -> Vec<Entity> {
         let entities = self.ecs.entities();
         let player = self.ecs.read_storage::<Player>();
@@ -258,6 +343,7 @@ impl State {
                 level: 0,
                 name: "Base".to_string(),
                 map_color: "#D4BF8E".to_string(),
+                terrain: None,
             }),
         )
     }
@@ -271,19 +357,33 @@ impl State {
                 .expect("Unable to delete entity");
         }
 
-        let is_overworld = map_builder_args.is_some();
+        // every level draws from fresh streams, so its layout only depends on the seed and
+        // how many levels came before it
+        let (mut mapgen_rng, mut spawn_rng) = {
+            let mut seed = self.ecs.fetch_mut::<RunSeed>();
+            seed.next_level();
+
+            let mut world_rng = self.ecs.fetch_mut::<rltk::RandomNumberGenerator>();
+            *world_rng = seed.stream(RngStream::World);
+
+            (
+                seed.stream(RngStream::MapGen),
+                seed.stream(RngStream::Spawn),
+            )
+        };
+
+        let is_overworld = difficulty == 0;
         let mut map_builder = if let Some(args) = map_builder_args {
-            map_builder::with_builder(&args)
+            map_builder::with_builder(&args, &mut mapgen_rng)
         } else {
-            map_builder::random_builder(80, 50, difficulty, "-".to_string())
+            map_builder::random_builder(80, 50, difficulty, "-".to_string(), &mut mapgen_rng)
         };
 
         let new_map = {
-            let mut rng = self.ecs.fetch_mut::<rltk::RandomNumberGenerator>();
             let mut done = false;
 
             while !done {
-                map_builder.build_map(&mut rng);
+                map_builder.build_map(&mut mapgen_rng);
                 let floor_count = map_builder
                     .build_data
                     .map
@@ -327,18 +427,81 @@ impl State {
         // fill the map
         if is_overworld {
             map_builder.spawn_overworld(&mut self.ecs);
+            self.ecs.fetch_mut::<MissionInfo>().contract = None;
+            self.ecs.fetch_mut::<AreaRun>().area = None;
         } else {
-            map_builder.spawn_entities(&mut self.ecs);
+            let (boss, is_final_floor) = {
+                let run = self.ecs.fetch::<AreaRun>();
+                (run.get_boss().map(str::to_string), run.is_final_floor())
+            };
+
+            if let Some(boss) = boss {
+                map_builder.spawn_boss(&mut self.ecs, &boss);
+            } else {
+                map_builder.spawn_entities(&mut self.ecs, &mut spawn_rng);
+                if is_final_floor {
+                    map_builder.spawn_guardian(&mut self.ecs, &mut spawn_rng);
+                }
+            }
+            self.start_mission();
+        }
+    }
+
+    fn start_mission(&mut self) {
+        let mut mission = self.ecs.fetch_mut::<MissionInfo>();
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        mission.reset();
+
+        let contract = match mission.contract {
+            None => return,
+            Some(contract) => contract,
+        };
+        log.add(format!("Contract: {}", contract.description()));
+
+        if contract == Contract::DefeatTarget {
+            // the toughest enemy in the arena gets marked
+            let entities = self.ecs.entities();
+            let healths = self.ecs.read_storage::<Health>();
+            let ais = self.ecs.read_storage::<AiState>();
+            let mut viewables = self.ecs.write_storage::<Viewable>();
+            let mut targets = self.ecs.write_storage::<MissionTarget>();
+
+            let target = (&entities, &healths, &ais)
+                .join()
+                .max_by_key(|(_, health, _)| health.max)
+                .map(|(entity, _, _)| entity);
+
+            if let Some(target) = target {
+                targets
+                    .insert(target, MissionTarget)
+                    .expect("Failed to mark mission target");
+                mission.add(target);
+
+                if let Some(view) = viewables.get_mut(target) {
+                    view.description.push("Marked by the Handler".to_string());
+                    log.add(format!(
+                        "The Handler has marked a {}",
+                        view.name.to_lowercase()
+                    ));
+                }
+            }
         }
     }
 
-    // spawn an exit near the player if less than 10% of enemies remain
+    // spawn an exit near the player once the contract is done, or without one, if less than 20%
+    // of enemies remain
     fn should_spawn_exit(&self) -> bool {
         let map = self.ecs.fetch::<Map>();
         if map.exit_spawned {
             return false;
         }
 
+        // nothing else matters while the guardian stands
+        let guardians = self.ecs.read_storage::<Guardian>();
+        if guardians.join().next().is_some() {
+            return false;
+        }
+
         let total = map.initial_spawns;
         let healths = self.ecs.read_storage::<Health>();
         let positions = self.ecs.read_storage::<Position>();
@@ -347,10 +510,11 @@ impl State {
             remaining += 1;
         }
 
-        if remaining <= 1 {
-            true
-        } else {
-            remaining - 1 <= total / 5
+        let mission = self.ecs.fetch::<MissionInfo>();
+        match mission.contract {
+            None | Some(Contract::NoPotions) => remaining <= 1 || remaining - 1 <= total / 5,
+            // once everyone is down, there is no way left to finish the contract
+            Some(_) => mission.is_done() || remaining <= 1,
         }
     }
 
@@ -376,12 +540,167 @@ impl State {
             map.tiles[random_loc] = TileType::DownStairs;
             map.exit_spawned = true;
             log.add("An exit portal has appeared!");
+
+            let bonus = map.level * consts::CLEAR_BONUS_PER_LEVEL;
+            self.player_inventory.money += bonus;
+            log.add(format!("You earn ${} for clearing the arena", bonus));
+
+            let mut mission = self.ecs.fetch_mut::<MissionInfo>();
+            if let Some(contract) = mission.contract {
+                if mission.succeeded() {
+                    let reward = map.level * contract.reward();
+                    self.player_inventory.money += reward;
+                    log.add(format!("Contract complete! The Handler pays ${}", reward));
+                } else {
+                    mission.failed = true;
+                    log.add("You failed the contract");
+                }
+            }
+
+            let run = self.ecs.fetch::<AreaRun>();
+            if run.is_final_floor() && run.tier > self.max_cleared_level {
+                self.max_cleared_level = run.tier;
+                log.add(format!("Arena {} is now open at the gate", run.tier + 1));
+
+                if self.saves_progress {
+                    if let Err(e) = saveload::save_progress(self.max_cleared_level) {
+                        log.add(format!("Unable to save progress: {}", e));
+                    }
+                }
+            }
         } else {
             // more robust handling for this
             unreachable!();
         }
     }
 
+    // the gate starts a run in the first arena until a tier has been cleared
+    fn open_gate(&mut self) -> RunState {
+        if self.max_cleared_level > 0 {
+            RunState::TierSelect {
+                index: self.max_cleared_level as usize,
+            }
+        } else {
+            self.enter_arena();
+            RunState::AwaitingInput
+        }
+    }
+
+    fn enter_arena(&mut self) {
+        self.change_map(1);
+    }
+
+    // start a descent through a new area
+    fn change_map(&mut self, tier: u32) {
+        {
+            let mut run = self.ecs.fetch_mut::<AreaRun>();
+            let mut rng = self.ecs.fetch_mut::<rltk::RandomNumberGenerator>();
+            run.start(tier, &mut rng);
+        }
+        self.load_floor();
+
+        let run = self.ecs.fetch::<AreaRun>();
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        if let Some(area) = &run.area {
+            log.add(format!("You enter the {}. Good luck challenger", area.name));
+        }
+    }
+
+    // taking the exit leads one floor down, or back to base once the guardian is beaten
+    fn next_floor(&mut self) {
+        if self.ecs.fetch::<AreaRun>().is_final_floor() {
+            self.load_overworld();
+            self.reset_player();
+
+            let mut log = self.ecs.fetch_mut::<GameLog>();
+            log.add("You return to base victorious");
+            return;
+        }
+
+        self.ecs.fetch_mut::<AreaRun>().floor += 1;
+        self.load_floor();
+
+        let run = self.ecs.fetch::<AreaRun>();
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        if let Some(boss) = run.get_boss() {
+            log.add(format!(
+                "The {} awaits you in its arena",
+                boss.to_lowercase()
+            ));
+        } else if run.is_final_floor() {
+            log.add("You sense a guardian on this floor");
+        } else {
+            log.add(format!("You descend to floor {}", run.floor));
+        }
+    }
+
+    fn load_floor(&mut self) {
+        let (level, args) = {
+            let run = self.ecs.fetch::<AreaRun>();
+            (run.get_level(), run.map_args())
+        };
+
+        self.new_level(level, args);
+        sys_visibility::VisibilitySystem.run_now(&self.ecs);
+    }
+
+    // bookkeeping after the player commits to an action
+    fn end_player_turn(&mut self) {
+        player::end_turn_cleanup(&mut self.ecs);
+
+        {
+            let mut mission = self.ecs.fetch_mut::<MissionInfo>();
+            if mission.contract.is_some() {
+                mission.turns += 1;
+            }
+        }
+
+        if self.should_spawn_exit() {
+            self.spawn_exit();
+        }
+    }
+
+    // swaps the player's moveset, and resizes their stamina to match the new weapon
+    fn equip_weapon(&mut self, weapon_type: weapon::WeaponType) {
+        self.player_weapon = weapon::get_upgraded_weapon(weapon_type, &self.player_inventory);
+
+        let player = self.ecs.fetch::<Entity>();
+        let mut stams = self.ecs.write_storage::<Stamina>();
+        if let Some(stamina) = stams.get_mut(*player) {
+            stamina.max = self.player_weapon.max_stamina;
+            stamina.current = stamina.max;
+        }
+
+        let mut log = self.ecs.fetch_mut::<GameLog>();
+        log.add(format!(
+            "You equip the {}",
+            self.player_weapon.name.to_lowercase()
+        ));
+    }
+
+    // while watching a replay, the keyboard only controls the playback
+    fn playback_input(&mut self, ctx: &mut Rltk) {
+        let playback = match &mut self.playback {
+            None => return,
+            Some(playback) => playback,
+        };
+
+        match ctx.key {
+            Some(rltk::VirtualKeyCode::Equals)
+            | Some(rltk::VirtualKeyCode::Plus)
+            | Some(rltk::VirtualKeyCode::NumpadAdd) => playback.speed_up(),
+            Some(rltk::VirtualKeyCode::Minus) | Some(rltk::VirtualKeyCode::NumpadSubtract) => {
+                playback.slow_down()
+            }
+            Some(rltk::VirtualKeyCode::Escape) => {
+                self.playback = None;
+                let mut log = self.ecs.fetch_mut::<GameLog>();
+                log.add("You take over from the replay");
+            }
+            _ => {}
+        }
+    }
+
     fn reset_player(&mut self) {
         let player = self.ecs.fetch::<Entity>();
         let mut healths = self.ecs.write_storage::<Health>();
@@ -421,22 +740,22 @@ impl GameState for State {
         }
 
         // draw map + gui
-        gui::map::draw_all(&self.ecs, ctx);
+        gui::map::draw_all(&self.ecs, ctx, self.show_danger);
 
         // non-map elements
         gui::sidebar::draw_sidebar(&self, ctx);
         gui::log::update_log_text(&self.ecs, ctx);
 
+        if self.playback.is_some() {
+            self.playback_input(ctx);
+        }
+
         match next_status {
             RunState::AwaitingInput => {
                 next_status = player::player_input(self, ctx);
 
                 if next_status == RunState::Running {
-                    player::end_turn_cleanup(&mut self.ecs);
-
-                    if self.should_spawn_exit() {
-                        self.spawn_exit();
-                    }
+                    self.end_player_turn();
                 }
             }
             RunState::Charging { dir, speed } => {
@@ -445,6 +764,7 @@ impl GameState for State {
             }
             RunState::Targetting {
                 attack_type,
+                source,
                 cursor_point,
                 validity_mode,
                 show_path,
@@ -452,14 +772,18 @@ impl GameState for State {
                 let range_type = crate::attack_type::get_attack_range(attack_type);
                 let tiles_in_range = crate::range_type::resolve_range_at(&range_type, player_point);
 
-                let result = player::ranged_target(
-                    self,
-                    ctx,
-                    cursor_point,
-                    tiles_in_range,
-                    validity_mode,
-                    show_path,
-                );
+                let result = if self.playback.is_some() {
+                    player::replay_target(self)
+                } else {
+                    player::ranged_target(
+                        self,
+                        ctx,
+                        cursor_point,
+                        tiles_in_range,
+                        validity_mode,
+                        show_path,
+                    )
+                };
                 match result.0 {
                     player::SelectionResult::Canceled => {
                         next_status = RunState::AwaitingInput;
@@ -468,6 +792,7 @@ impl GameState for State {
                         if let Some(new_cursor) = result.1 {
                             next_status = RunState::Targetting {
                                 attack_type,
+                                source,
                                 cursor_point: new_cursor,
                                 validity_mode,
                                 show_path,
@@ -475,27 +800,21 @@ impl GameState for State {
                         }
                     }
                     player::SelectionResult::Selected => {
-                        {
-                            // we should generally have a target at this point
-                            // if we don't have a point, assume its because we won't need one later
-                            let target = result.1.unwrap_or(rltk::Point::zero());
-
-                            let mut attacks = self.ecs.write_storage::<AttackIntent>();
-                            let mut frames = self.ecs.write_storage::<FrameData>();
-                            let player = self.ecs.fetch::<Entity>();
-
-                            attacks
-                                .insert(*player, get_attack_intent(attack_type, target, None))
-                                .ok();
-
-                            frames.insert(*player, get_frame_data(attack_type)).ok();
-
-                            // TODO: remove attack_modifier
-                            self.attack_modifier = None;
-                        }
-
-                        next_status = RunState::Running;
-                        player::end_turn_cleanup(&mut self.ecs);
+                        // we should generally have a target at this point
+                        // if we don't have a point, assume its because we won't need one later
+                        let target = result.1.unwrap_or(rltk::Point::zero());
+                        next_status = player::apply_command(
+                            self,
+                            player::PlayerCommand::Target {
+                                attack_type,
+                                source,
+                                target,
+                            },
+                        );
+
+                        // TODO: remove attack_modifier
+                        self.attack_modifier = None;
+                        self.end_player_turn();
                     }
                 }
             }
@@ -519,40 +838,32 @@ impl GameState for State {
                 }
             }
             RunState::GenerateLevel => {
-                self.new_level(1, None);
-                sys_visibility::VisibilitySystem.run_now(&self.ecs);
-
-                let mut log = self.ecs.fetch_mut::<GameLog>();
-                log.add("You enter the arena. Good luck challenger");
-                next_status = RunState::AwaitingInput;
+                next_status = self.open_gate();
             }
             RunState::ChangeMap { level } => {
-                self.new_level(level, None);
-                sys_visibility::VisibilitySystem.run_now(&self.ecs);
-
-                let mut log = self.ecs.fetch_mut::<GameLog>();
-                log.add(format!("You enter arena {}", level));
+                self.change_map(level);
+                next_status = RunState::AwaitingInput;
+            }
+            RunState::NextFloor => {
+                self.next_floor();
                 next_status = RunState::AwaitingInput;
             }
             RunState::Dead { success } => {
                 gui::log::expanded_log(&self.ecs, ctx);
-                match ctx.key {
-                    None => {}
-                    Some(key) => {
-                        if key == rltk::VirtualKeyCode::R {
-                            self.load_overworld();
-                            self.reset_player();
-
-                            next_status = RunState::Running;
-                        } else {
-                            let mut log = self.ecs.fetch_mut::<GameLog>();
-                            log.add("You are knocked out! Press r to try again");
-                        }
-                    }
+                let from_keys = (ctx.key == Some(rltk::VirtualKeyCode::R))
+                    .then_some(player::PlayerCommand::Retry);
+
+                if let Some(command @ player::PlayerCommand::Retry) =
+                    player::next_command(self, from_keys)
+                {
+                    next_status = player::apply_command(self, command);
+                } else if ctx.key.is_some() && self.playback.is_none() {
+                    let mut log = self.ecs.fetch_mut::<GameLog>();
+                    log.add("You are knocked out! Press r to try again");
                 }
             }
             RunState::AbilitySelect { index } => {
-                if self.player_abilities.is_empty() {
+                if self.player_weapon.abilities.is_empty() {
                     let mut log = self.ecs.fetch_mut::<GameLog>();
                     log.add("You know no abilities");
                     next_status = RunState::Running;
@@ -561,21 +872,41 @@ impl GameState for State {
                     next_status = player::ability_select_input(self, ctx, index);
 
                     if next_status == RunState::Running {
-                        player::end_turn_cleanup(&mut self.ecs);
+                        self.end_player_turn();
                     }
                 }
             }
+            RunState::WeaponSelect { index } => {
+                gui::weapon_select::draw_weapons(self, ctx, index);
+                next_status = player::weapon_select_input(self, ctx, index);
+            }
+            RunState::UpgradeSelect { index } => {
+                gui::overworld::draw_upgrades(self, ctx, index);
+                next_status = player::upgrade_select_input(self, ctx, index);
+            }
+            RunState::ShopSelect { index } => {
+                gui::overworld::draw_shop(self, ctx, index);
+                next_status = player::shop_select_input(self, ctx, index);
+            }
+            RunState::MissionSelect { index } => {
+                gui::overworld::draw_missions(self, ctx, index);
+                next_status = player::mission_select_input(self, ctx, index);
+            }
+            RunState::TierSelect { index } => {
+                gui::overworld::draw_tiers(self, ctx, index);
+                next_status = player::tier_select_input(self, ctx, index);
+            }
             RunState::InventorySelect { index } => {
                 if self.player_inventory.consumables.is_empty() {
                     let mut log = self.ecs.fetch_mut::<GameLog>();
                     log.add("You have no items");
-                    next_status = RunState::Running;
+                    next_status = RunState::AwaitingInput;
                 } else {
                     gui::inventory::draw_inventory(self, ctx, index);
                     next_status = player::inventory_select_input(self, ctx, index);
 
                     if next_status == RunState::Running {
-                        player::end_turn_cleanup(&mut self.ecs);
+                        self.end_player_turn();
                     }
                 }
             }
@@ -602,6 +933,18 @@ impl GameState for State {
 
 fn main() -> rltk::BError {
     use rltk::RltkBuilder;
+
+    let args: Vec<String> = std::env::args().collect();
+    let seed = run_seed::seed_from_args(&args)?
+        .or_else(run_seed::seed_from_url)
+        .map_or_else(RunSeed::random, RunSeed::new);
+
+    data::check_data()?;
+
+    if let Some(config) = headless::HeadlessConfig::from_args(&args)? {
+        return headless::run(config, seed);
+    }
+
     rltk::link_resource!(FONT, "resources/Zilk-16x16.png");
     rltk::link_resource!(ICONS, "resources/custom_icons.png");
 
@@ -628,33 +971,37 @@ fn main() -> rltk::BError {
         .build()
         .expect("Failed to build console");
 
-    let mut gs = State {
-        ecs: World::new(),
-        tick: 0,
-        tab_targets: Vec::new(),
-        tab_index: 0,
-        attack_modifier: None,
-        player_inventory: inventory::Inventory::new(),
-        player_charging: (false, crate::Direction::N, 0, false),
-        player_abilities: pabb(),
-        max_cleared_level: 0,
-    };
+    let mut gs = State::new();
+
+    if let Some(replay) = replay::replay_from_args(&args)? {
+        gs.new_game(replay.seed);
+        gs.playback = Some(replay::Playback::from_replay(&replay));
+        gs.max_cleared_level = replay.max_cleared_level;
+        gs.saves_progress = false;
+        gs.recording = Some(replay);
+        gs.ecs
+            .fetch_mut::<gamelog::GameLog>()
+            .add("Watching a replay. [+/-] to change speed, [esc] to take over");
+        return rltk::main_loop(context, gs);
+    }
+
+    gs.new_game(seed);
+    gs.max_cleared_level = saveload::load_progress();
+    if let Some(recording) = &mut gs.recording {
+        recording.max_cleared_level = gs.max_cleared_level;
+    }
 
-    gs.new_game();
+    match saveload::load_game(&mut gs) {
+        Ok(true) => gs
+            .ecs
+            .fetch_mut::<gamelog::GameLog>()
+            .add("Welcome back, challenger"),
+        Ok(false) => {}
+        Err(e) => gs
+            .ecs
+            .fetch_mut::<gamelog::GameLog>()
+            .add(format!("Unable to load save: {}", e)),
+    }
 
     rltk::main_loop(context, gs)
 }
-
-use crate::weapon::lance::LanceAttack;
-fn pabb() -> Vec<AttackData> {
-    let mut attacks = Vec::new();
-    attacks.push(crate::weapon::lance::get_attack_data(
-        LanceAttack::DrawAttack,
-    ));
-    attacks.push(crate::weapon::lance::get_attack_data(LanceAttack::Sweep));
-    attacks.push(crate::weapon::lance::get_attack_data(LanceAttack::Charge));
-    attacks.push(crate::weapon::lance::get_attack_data(LanceAttack::Hook));
-    attacks.push(crate::weapon::lance::get_attack_data(LanceAttack::Shove));
-
-    attacks
-}
@@ -1,9 +1,10 @@
 use rltk::{Algorithm2D, BaseMap, Point, Rect};
+use serde::{Deserialize, Serialize};
 use specs::Entity;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum TileType {
     Wall,
     Floor,
@@ -11,6 +12,26 @@ pub enum TileType {
     ShallowWater,
     DownStairs,
     NewLevel,
+    // hurts anything knocked into it, and sets it alight
+    Lava,
+    // hurts anything knocked onto it
+    Spikes,
+    // anything pushed onto it keeps sliding
+    Ice,
+    // catches fire from fire attacks
+    Grass,
+    BurningGrass,
+}
+
+// what happens to something knocked onto a tile
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Hazard {
+    // hurts and sets alight
+    Lava,
+    // hurts
+    Spikes,
+    // stuns, and counts as a dunk
+    Water,
 }
 
 #[derive(Default, Clone)]
@@ -19,7 +40,7 @@ struct SearchArgs {
     multi_component: Option<Vec<crate::MonsterPart>>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Map {
     pub tiles: Vec<TileType>,
     pub rooms: Vec<Rect>,
@@ -29,7 +50,10 @@ pub struct Map {
     pub name: String,
     pub camera: crate::Camera,
     pub color_map: Vec<rltk::RGB>,
+    // entity indexes are rebuilt after loading a save
+    #[serde(skip)]
     pub item_map: HashMap<usize, Entity>,
+    #[serde(skip)]
     pub creature_map: HashMap<usize, Entity>,
     pub known_tiles: Vec<bool>,
     pub visible_tiles: Vec<bool>,
@@ -37,6 +61,10 @@ pub struct Map {
     pub blocked_vision: Vec<bool>,
     pub initial_spawns: i32,
     pub exit_spawned: bool,
+    // ticks left on each burning tile
+    #[serde(default)]
+    pub fires: HashMap<usize, u32>,
+    #[serde(skip)]
     search_args: SearchArgs,
 }
 
@@ -122,6 +150,7 @@ impl Map {
             blocked_vision: vec![false; dim], // this is probably sparse?
             initial_spawns: 0,
             exit_spawned: false,
+            fires: HashMap::new(),
             search_args: SearchArgs::default(),
         }
     }
@@ -136,9 +165,26 @@ impl Map {
     }
 
     pub fn set_blocked_tiles(&mut self) {
-        for (index, tile) in self.tiles.iter_mut().enumerate() {
-            let is_blocked = *tile == TileType::Wall || *tile == TileType::Water;
-            self.blocked_tiles[index] = is_blocked;
+        for index in 0..self.tiles.len() {
+            self.blocked_tiles[index] = self.is_terrain_blocked(index);
+        }
+    }
+
+    // tiles nobody can stand on, whoever else is around
+    pub fn is_terrain_blocked(&self, index: usize) -> bool {
+        matches!(
+            self.tiles[index],
+            TileType::Wall | TileType::Water | TileType::Lava
+        )
+    }
+
+    // terrain that stops anything knocked onto it, and does something to it
+    pub fn get_hazard(&self, index: usize) -> Option<Hazard> {
+        match self.tiles[index] {
+            TileType::Lava => Some(Hazard::Lava),
+            TileType::Spikes => Some(Hazard::Spikes),
+            TileType::ShallowWater => Some(Hazard::Water),
+            _ => None,
         }
     }
 
@@ -199,6 +245,26 @@ impl Map {
         self.tiles[index] == TileType::Water || self.tiles[index] == TileType::ShallowWater
     }
 
+    pub fn is_tile_hazard(&self, x: i32, y: i32) -> bool {
+        if x < 0 || x > self.width - 1 || y < 0 || y > self.height - 1 {
+            return false;
+        }
+
+        let index = self.get_index(x, y);
+        self.tiles[index] == TileType::Lava || self.tiles[index] == TileType::Spikes
+    }
+
+    // grass only, so burnt out tiles don't catch again
+    pub fn ignite(&mut self, index: usize) -> bool {
+        if self.tiles[index] != TileType::Grass {
+            return false;
+        }
+
+        self.tiles[index] = TileType::BurningGrass;
+        self.fires.insert(index, crate::consts::FIRE_DURATION);
+        true
+    }
+
     fn is_exit_valid(&self, x: i32, y: i32) -> bool {
         if !self.is_tile_valid(x, y) {
             return false;
@@ -277,7 +343,8 @@ impl Map {
         for part in &multi_component.part_list {
             for part_pos in part.symbol_map.keys() {
                 let part_pos_index = self.point2d_to_index(*part_pos + point);
-                self.blocked_tiles[part_pos_index] = is_blocked;
+                self.blocked_tiles[part_pos_index] =
+                    is_blocked || self.is_terrain_blocked(part_pos_index);
 
                 if is_blocked {
                     self.creature_map.insert(part_pos_index, entity);
@@ -335,16 +402,28 @@ impl Map {
         next: Point,
         multi_component: Option<&crate::MultiTile>,
     ) -> bool {
-        let prev_index = self.point2d_to_index(prev);
-        let next_index = self.point2d_to_index(next);
-
         // if the destination is blocked by something other than us, quit moving
         if !self.is_exit_valid_for(next.x, next.y, creature, multi_component) {
             return false;
         }
 
+        self.place_creature(creature, prev, next, multi_component);
+        true
+    }
+
+    // move a creature without checking the destination, for pushes that can land in lava or water
+    pub fn place_creature(
+        &mut self,
+        creature: Entity,
+        prev: Point,
+        next: Point,
+        multi_component: Option<&crate::MultiTile>,
+    ) {
+        let prev_index = self.point2d_to_index(prev);
+        let next_index = self.point2d_to_index(next);
+
         self.creature_map.remove(&prev_index);
-        self.blocked_tiles[prev_index] = false;
+        self.blocked_tiles[prev_index] = self.is_terrain_blocked(prev_index);
         if let Some(multi_component) = multi_component {
             self.update_multi_component(creature, multi_component, prev_index, false);
         }
@@ -354,7 +433,5 @@ impl Map {
         if let Some(multi_component) = multi_component {
             self.update_multi_component(creature, multi_component, next_index, true);
         }
-
-        true
     }
 }
@@ -0,0 +1,47 @@
+use super::{BuilderMap, InitialMapBuilder};
+use crate::*;
+
+// An open room with a few pillars to hide behind. The player comes in on the left, leaving the
+// rest of the room for the boss
+pub struct BossArenaBuilder;
+
+impl InitialMapBuilder for BossArenaBuilder {
+    fn build_map(&mut self, build_data: &mut BuilderMap, rng: &mut rltk::RandomNumberGenerator) {
+        self.build(build_data, rng);
+    }
+}
+
+impl BossArenaBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self)
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap, _rng: &mut rltk::RandomNumberGenerator) {
+        let width = build_data.map.width;
+        let height = build_data.map.height;
+
+        for x in 1..width - 1 {
+            for y in 1..height - 1 {
+                let index = build_data.map.get_index(x, y);
+                build_data.map.tiles[index] = TileType::Floor;
+            }
+        }
+
+        for (x, y) in [
+            (width / 3, height / 4),
+            (width / 3, height * 3 / 4),
+            (width * 2 / 3, height / 4),
+            (width * 2 / 3, height * 3 / 4),
+        ] {
+            let index = build_data.map.get_index(x, y);
+            build_data.map.tiles[index] = TileType::Wall;
+        }
+
+        build_data.starting_position = Position {
+            x: 2,
+            y: height / 2,
+        };
+
+        build_data.take_snapshot();
+    }
+}
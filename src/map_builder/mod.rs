@@ -1,6 +1,7 @@
 use crate::*;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+mod boss_arena;
 mod common;
 mod lake_spawner;
 mod map_culler;
@@ -8,6 +9,7 @@ mod noise_region;
 mod room_corridor;
 mod room_drawer;
 mod starting_pos;
+mod terrain_spawner;
 
 pub mod drunk_walk;
 pub mod overworld;
@@ -19,7 +21,7 @@ pub struct BuilderMap {
     pub map: Map,
     pub history: Vec<Map>,
     pub starting_position: Position,
-    pub noise_areas: HashMap<i32, Vec<usize>>,
+    pub noise_areas: BTreeMap<i32, Vec<usize>>,
     pub rooms: Option<Vec<rltk::Rect>>,
     pub corridors: Option<Vec<Vec<usize>>>,
 }
@@ -44,6 +46,7 @@ pub struct MapBuilderArgs {
     pub builder_type: usize,
     pub name: String,
     pub map_color: String,
+    pub terrain: Option<TileType>,
 }
 
 pub struct BuilderChain {
@@ -68,7 +71,7 @@ impl BuilderChain {
                 ),
                 starting_position: Position { x: 0, y: 0 },
                 history: Vec::new(),
-                noise_areas: HashMap::new(),
+                noise_areas: BTreeMap::new(),
                 rooms: None,
                 corridors: None,
             },
@@ -100,18 +103,87 @@ impl BuilderChain {
         }
     }
 
-    pub fn spawn_entities(&mut self, ecs: &mut World) {
+    pub fn spawn_entities(&mut self, ecs: &mut World, rng: &mut rltk::RandomNumberGenerator) {
         let mut count = 0;
 
-        // random spawns in each area of minor monsters and resources
-        for area in self.build_data.noise_areas.iter() {
-            count += spawn::spawner::spawn_region(ecs, area.1, self.build_data.map.level as i32);
+        // random spawns in each area of minor monsters and resources, each area's monsters
+        // forming a squad
+        for (squad, area) in self.build_data.noise_areas.iter() {
+            count += spawn::spawner::spawn_region(
+                ecs,
+                rng,
+                area,
+                self.build_data.map.level as i32,
+                *squad,
+            );
         }
 
         let mut map = ecs.fetch_mut::<Map>();
         map.initial_spawns = count;
     }
 
+    // the open floor tile farthest from the start that a body covering these offsets fits into
+    fn get_farthest_spawn(&self, ecs: &World, offsets: &[rltk::Point]) -> Option<usize> {
+        let start = self.build_data.starting_position.as_point();
+        let map = ecs.fetch::<Map>();
+        let is_open = |point: rltk::Point| {
+            map.in_bounds(point) && {
+                let idx = map.point2d_to_index(point);
+                map.tiles[idx] == TileType::Floor && !map.blocked_tiles[idx]
+            }
+        };
+
+        (0..map.tiles.len())
+            .map(|idx| map.index_to_point2d(idx))
+            .filter(|point| {
+                is_open(*point) && offsets.iter().all(|offset| is_open(*point + *offset))
+            })
+            .max_by_key(|point| rltk::DistanceAlg::Manhattan.distance2d(start, *point) as i32)
+            .map(|point| map.point2d_to_index(point))
+    }
+
+    // the guardian waits as far from the start as it can
+    pub fn spawn_guardian(&mut self, ecs: &mut World, rng: &mut rltk::RandomNumberGenerator) {
+        let spawn_index = match self.get_farthest_spawn(ecs, &[]) {
+            None => return,
+            Some(spawn_index) => spawn_index,
+        };
+
+        let point = ecs.fetch::<Map>().index_to_point2d(spawn_index);
+        let difficulty = self.build_data.map.level as i32;
+        if let Some(guardian) = spawn::spawner::build_guardian(ecs, rng, difficulty, point) {
+            spawn::spawner::track_entity(ecs, guardian, spawn_index);
+
+            let mut map = ecs.fetch_mut::<Map>();
+            map.initial_spawns += 1;
+        }
+    }
+
+    // bosses get their arena to themselves
+    pub fn spawn_boss(&mut self, ecs: &mut World, name: &str) {
+        let offsets: Vec<rltk::Point> = match crate::data::boss_info::get_boss_info(name) {
+            None => return,
+            Some(info) => info
+                .parts
+                .iter()
+                .flat_map(|part| part.tiles.iter().copied())
+                .collect(),
+        };
+
+        let spawn_index = match self.get_farthest_spawn(ecs, &offsets) {
+            None => return,
+            Some(spawn_index) => spawn_index,
+        };
+
+        let point = ecs.fetch::<Map>().index_to_point2d(spawn_index);
+        if let Some(boss) = spawn::spawner::build_boss(ecs, name, point) {
+            spawn::spawner::track_entity(ecs, boss, spawn_index);
+
+            let mut map = ecs.fetch_mut::<Map>();
+            map.initial_spawns = 1;
+        }
+    }
+
     pub fn spawn_overworld(&mut self, ecs: &mut World) {
         crate::spawn::spawner::build_npc_blacksmith(ecs, rltk::Point::new(13, 5));
         crate::spawn::spawner::build_npc_shopkeeper(ecs, rltk::Point::new(5, 5));
@@ -128,31 +200,43 @@ pub trait MetaMapBuilder {
     fn build_map(&mut self, build_data: &mut BuilderMap, rng: &mut rltk::RandomNumberGenerator);
 }
 
-pub fn random_builder(width: i32, height: i32, level: u32, name: String) -> BuilderChain {
-    let mut rng = rltk::RandomNumberGenerator::new();
+pub fn random_builder(
+    width: i32,
+    height: i32,
+    level: u32,
+    name: String,
+    rng: &mut rltk::RandomNumberGenerator,
+) -> BuilderChain {
     let builder_type = rng.range(0, 5);
     println!("Building map type {}", builder_type);
 
-    with_builder(&MapBuilderArgs {
-        builder_type: builder_type as usize,
-        width,
-        height,
-        level,
-        name,
-        map_color: "#FFFFFF".to_string(),
-    })
+    with_builder(
+        &MapBuilderArgs {
+            builder_type: builder_type as usize,
+            width,
+            height,
+            level,
+            name,
+            map_color: "#FFFFFF".to_string(),
+            terrain: None,
+        },
+        rng,
+    )
 }
 
-pub fn with_builder(args: &MapBuilderArgs) -> BuilderChain {
-    let mut rng = rltk::RandomNumberGenerator::new();
-    let mut builder = BuilderChain::new(args, &mut rng);
+pub fn with_builder(args: &MapBuilderArgs, rng: &mut rltk::RandomNumberGenerator) -> BuilderChain {
+    let mut builder = BuilderChain::new(args, rng);
 
-    get_builder(&mut builder, args.builder_type, &mut rng);
+    get_builder(&mut builder, args.builder_type, rng);
 
-    if args.builder_type != 99 {
+    // the overworld and boss arenas are laid out by hand
+    if args.builder_type != 99 && args.builder_type != 98 {
         builder.with(noise_region::NoiseRegion::new());
         builder.with(lake_spawner::LakeSpawner::new());
         builder.with(lake_spawner::LakeEroder::new());
+        if let Some(terrain) = args.terrain {
+            builder.with(terrain_spawner::TerrainSpawner::new(terrain));
+        }
         builder.with(map_culler::MapCuller::new());
 
         // refresh noise regions for spawn placements
@@ -180,6 +264,7 @@ fn get_builder(
         2 => builder.starts_with(drunk_walk::DrunkardsWalkBuilder::open_area()),
         3 => builder.starts_with(drunk_walk::DrunkardsWalkBuilder::open_halls()),
         4 => builder.starts_with(drunk_walk::DrunkardsWalkBuilder::winding_passages()),
+        98 => builder.starts_with(boss_arena::BossArenaBuilder::new()),
         99 => builder.starts_with(overworld::OverworldBuilder::new()),
         _ => unreachable!(), //_ => Box::new(SimpleMapBuilder::new(new_depth)),
     }
@@ -1,7 +1,7 @@
 use super::{BuilderMap, MetaMapBuilder};
 use crate::TileType;
 use noise::*;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct NoiseRegion;
 
@@ -23,7 +23,7 @@ impl NoiseRegion {
         build_data: &mut BuilderMap,
         rng: &mut rltk::RandomNumberGenerator,
     ) {
-        let mut noise_areas: HashMap<i32, Vec<usize>> = HashMap::new();
+        let mut noise_areas: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
         let generator = Worley::new(rng.roll_dice(1, 65536) as u32)
             .set_frequency(0.05)
             .set_return_type(core::worley::ReturnType::Value);
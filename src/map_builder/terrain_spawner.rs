@@ -0,0 +1,84 @@
+use super::{BuilderMap, MetaMapBuilder};
+use crate::TileType;
+use std::cmp::min;
+
+pub struct TerrainSpawnerSettings {
+    pub region_chance: f32,
+    pub max_regions: u32,
+}
+
+// Lays an area's terrain over some of the noise regions, the same way lakes are placed
+pub struct TerrainSpawner {
+    terrain: TileType,
+    settings: TerrainSpawnerSettings,
+}
+
+impl MetaMapBuilder for TerrainSpawner {
+    fn build_map(&mut self, build_data: &mut BuilderMap, rng: &mut rltk::RandomNumberGenerator) {
+        self.build(build_data, rng);
+    }
+}
+
+impl TerrainSpawner {
+    pub fn new(terrain: TileType) -> Box<Self> {
+        Box::new(Self {
+            terrain,
+            settings: TerrainSpawnerSettings {
+                region_chance: 0.4,
+                max_regions: 3,
+            },
+        })
+    }
+
+    // spikes are dotted around rather than filling the whole region
+    fn get_density(&self) -> f32 {
+        match self.terrain {
+            TileType::Spikes => 0.25,
+            _ => 1.0,
+        }
+    }
+
+    fn build(&mut self, build_data: &mut BuilderMap, rng: &mut rltk::RandomNumberGenerator) {
+        if build_data.noise_areas.is_empty() {
+            return;
+        }
+
+        let start_idx = build_data.map.get_index(
+            build_data.starting_position.x,
+            build_data.starting_position.y,
+        );
+        let max_regions = min(
+            self.settings.max_regions,
+            (build_data.noise_areas.len() - 1).try_into().unwrap(),
+        );
+        let density = self.get_density();
+        let mut region_count = 0;
+        let mut removal = Vec::new();
+
+        for (key, region) in build_data.noise_areas.iter() {
+            if region_count >= max_regions {
+                break;
+            }
+
+            // never cover up where the player arrives
+            if region.contains(&start_idx) || rng.rand::<f32>() >= self.settings.region_chance {
+                continue;
+            }
+
+            region_count += 1;
+            removal.push(*key);
+
+            for t in region {
+                if build_data.map.tiles[*t] == TileType::Floor && rng.rand::<f32>() < density {
+                    build_data.map.tiles[*t] = self.terrain;
+                }
+            }
+        }
+
+        for key in removal {
+            build_data.noise_areas.remove(&key);
+        }
+
+        build_data.take_snapshot();
+    }
+}
@@ -1,12 +1,74 @@
+use serde::{Deserialize, Serialize};
 use specs::Entity;
 
+// Objectives the Handler offers before a run. A contract replaces the usual exit condition of
+// knocking out most of the arena
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Contract {
+    DefeatTarget,
+    Survive { turns: u32 },
+    Dunk { count: u32 },
+    NoPotions,
+}
+
+pub const CONTRACT_OFFERS: [Contract; 4] = [
+    Contract::DefeatTarget,
+    Contract::Survive { turns: 40 },
+    Contract::Dunk { count: 2 },
+    Contract::NoPotions,
+];
+
+impl Contract {
+    pub fn name(&self) -> String {
+        match self {
+            Contract::DefeatTarget => "Bounty".to_string(),
+            Contract::Survive { .. } => "Endurance".to_string(),
+            Contract::Dunk { .. } => "Splash".to_string(),
+            Contract::NoPotions => "Iron will".to_string(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Contract::DefeatTarget => "Knock out the marked enemy".to_string(),
+            Contract::Survive { turns } => format!("Survive for {} turns", turns),
+            Contract::Dunk { count } => format!("Knock {} enemies into water", count),
+            Contract::NoPotions => "Clear the arena without potions".to_string(),
+        }
+    }
+
+    // paid on top of the usual clear bonus, for each level of the arena
+    pub fn reward(&self) -> u32 {
+        match self {
+            Contract::DefeatTarget => 8,
+            Contract::Survive { .. } => 6,
+            Contract::Dunk { .. } => 10,
+            Contract::NoPotions => 8,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct MissionInfo {
+    // taken from the Handler, and started in the next arena
+    pub next_contract: Option<Contract>,
+    pub contract: Option<Contract>,
+    pub turns: u32,
+    pub dunked: u32,
+    pub failed: bool,
+    // rebuilt from MissionTarget components when loading
+    #[serde(skip)]
     pub remaining: Vec<Entity>,
 }
 
 impl MissionInfo {
     pub fn new() -> Self {
         Self {
+            next_contract: None,
+            contract: None,
+            turns: 0,
+            dunked: 0,
+            failed: false,
             remaining: Vec::new(),
         }
     }
@@ -22,10 +84,50 @@ impl MissionInfo {
     }
 
     pub fn is_done(&self) -> bool {
-        self.remaining.is_empty()
+        match self.contract {
+            None | Some(Contract::NoPotions) => false,
+            Some(Contract::DefeatTarget) => self.remaining.is_empty(),
+            Some(Contract::Survive { turns }) => self.turns >= turns,
+            Some(Contract::Dunk { count }) => self.dunked >= count,
+        }
+    }
+
+    // checked once the exit appears, since some contracts only fail along the way
+    pub fn succeeded(&self) -> bool {
+        match self.contract {
+            None => false,
+            Some(Contract::NoPotions) => !self.failed,
+            Some(_) => self.is_done(),
+        }
     }
 
+    // a new arena starts whichever contract was taken last, or none at all
     pub fn reset(&mut self) {
+        self.contract = self.next_contract.take();
+        self.turns = 0;
+        self.dunked = 0;
+        self.failed = false;
         self.remaining = Vec::new();
     }
+
+    pub fn objective(&self) -> Option<String> {
+        let contract = self.contract?;
+        if self.failed {
+            return Some("Contract failed".to_string());
+        }
+
+        let text = match contract {
+            Contract::DefeatTarget if self.is_done() => "Target down".to_string(),
+            Contract::DefeatTarget => "Defeat the target".to_string(),
+            Contract::Survive { turns } => {
+                format!("Survive {}/{}", std::cmp::min(self.turns, turns), turns)
+            }
+            Contract::Dunk { count } => {
+                format!("Dunk {}/{}", std::cmp::min(self.dunked, count), count)
+            }
+            Contract::NoPotions => "No potions".to_string(),
+        };
+
+        Some(text)
+    }
 }
@@ -1,10 +1,20 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MonsterPart {
     pub symbol_map: HashMap<rltk::Point, rltk::FontCharType>,
     pub health: i32,
     pub max_health: i32,
+    #[serde(default)]
+    pub name: String,
+    // attacks that go with the part when it breaks
+    #[serde(default)]
+    pub moves: Vec<(crate::AttackType, f32)>,
+    #[serde(default)]
+    pub loot: Option<String>,
+    #[serde(default)]
+    pub broken: bool,
 }
 
 impl MonsterPart {
@@ -30,6 +40,22 @@ impl MonsterPart {
     }
 }
 
+// offsets from the anchor of every tile a body covers, in a fixed order
+pub fn get_footprint(multi: Option<&crate::MultiTile>) -> Vec<rltk::Point> {
+    let mut footprint = vec![rltk::Point::zero()];
+    if let Some(multi) = multi {
+        let mut part_tiles: Vec<rltk::Point> = multi
+            .part_list
+            .iter()
+            .flat_map(|part| part.symbol_map.keys().copied())
+            .collect();
+        part_tiles.sort_by_key(|point| (point.y, point.x));
+        footprint.extend(part_tiles);
+    }
+
+    footprint
+}
+
 pub fn all_bounds(part_list: &Vec<MonsterPart>) -> rltk::Rect {
     let mut bounds = rltk::Rect::zero();
     for part in part_list {
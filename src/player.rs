@@ -1,5 +1,6 @@
 use crate::*;
 use rltk::{Point, VirtualKeyCode};
+use serde::{Deserialize, Serialize};
 
 pub const DODGE_STAM_REQ: i32 = 5;
 pub const HOOK_STAM_REQ: i32 = 3;
@@ -7,7 +8,63 @@ pub const SHOVE_STAM_REQ: i32 = 3;
 pub const SUPLEX_STAM_REQ: i32 = 2;
 pub const BOLT_STAM_REQ: i32 = 2;
 
-fn try_move_player(ecs: &mut World, dx: i32, dy: i32) -> RunState {
+// where a targeted attack came from, by its place in the weapon's abilities or the inventory
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum AttackSource {
+    Ability { index: usize },
+    Item { index: usize },
+}
+
+// a single player decision, independent of how it was input
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum PlayerCommand {
+    Move {
+        dir: crate::Direction,
+    },
+    Wait,
+    Dodge,
+    // raise a guard that blocks until the next action, and parries hits that land right away
+    Guard,
+    Ability {
+        index: usize,
+        target: Option<Point>,
+    },
+    // a target picked in the targetting screen
+    Target {
+        attack_type: AttackType,
+        source: AttackSource,
+        target: Point,
+    },
+    // go back to the overworld after being knocked out
+    Retry,
+    // swap weapons, only possible in the overworld
+    Equip {
+        weapon_type: crate::weapon::WeaponType,
+    },
+    // spend money at the blacksmith
+    Upgrade {
+        upgrade: crate::upgrade::Upgrade,
+    },
+    // spend money at the shop, by the item's place in the stock
+    Buy {
+        index: usize,
+    },
+    // use a carried item, by its place in the inventory
+    UseItem {
+        index: usize,
+        target: Option<Point>,
+    },
+    // start a run from an unlocked tier, only possible in the overworld
+    EnterArena {
+        level: u32,
+    },
+    // accept a contract from the Handler for the next arena
+    TakeContract {
+        contract: crate::Contract,
+    },
+}
+
+fn try_move_player(ecs: &mut World, dx: i32, dy: i32, bump_attack: AttackType) -> RunState {
     let mut positions = ecs.write_storage::<Position>();
     let players = ecs.read_storage::<Player>();
     let mut movements = ecs.write_storage::<MoveIntent>();
@@ -31,14 +88,11 @@ fn try_move_player(ecs: &mut World, dx: i32, dy: i32) -> RunState {
         }
 
         match map.tiles[dest_index] {
-            TileType::DownStairs => {
-                return RunState::ChangeMap {
-                    level: map.level + 1,
-                }
-            }
+            TileType::DownStairs => return RunState::NextFloor,
             TileType::NewLevel => return RunState::GenerateLevel,
             TileType::ShallowWater => log.late_add("The shallow water slows you down"),
             TileType::Water => log.add("The water looks too deep to move through"),
+            TileType::Lava => log.add("The lava is far too hot to cross"),
             TileType::Wall => log.add("You bump into a wall"),
             _ => {}
         }
@@ -67,30 +121,23 @@ fn try_move_player(ecs: &mut World, dx: i32, dy: i32) -> RunState {
             } else if let Some(npc) = npcs.get(*dest_ent) {
                 match npc.npc_type {
                     NpcType::Blacksmith => {
-                        log.add("Good luck in the arena!");
-                        return RunState::AwaitingInput;
+                        return RunState::UpgradeSelect { index: 0 };
                     }
                     NpcType::Handler => {
-                        log.add(
-                            "Watch out, attacks have a recovery time that can leave you exposed",
-                        );
-                        return RunState::AwaitingInput;
+                        return RunState::MissionSelect { index: 0 };
                     }
                     NpcType::Shopkeeper => {
-                        log.add(
-                            "Most attacks have a windup and can be interrupted by the right attack",
-                        );
-                        return RunState::AwaitingInput;
+                        return RunState::ShopSelect { index: 0 };
                     }
                 }
             } else {
                 // bump attack
                 let attack = crate::attack_type::get_attack_intent(
-                    AttackType::MeleeKnockback,
+                    bump_attack,
                     Point::new(new_x, new_y),
                     None,
                 );
-                let frame = crate::attack_type::get_frame_data(AttackType::MeleeKnockback);
+                let frame = crate::attack_type::get_frame_data(bump_attack);
 
                 attacks
                     .insert(*player, attack)
@@ -123,13 +170,18 @@ fn try_move_charging(
         return RunState::Running;
     } else if input_dir == movement_dir.left() || input_dir == movement_dir.right() {
         let dir_point = input_dir.to_point();
-        return try_move_player(&mut gs.ecs, dir_point.x, dir_point.y);
+        return try_move_player(
+            &mut gs.ecs,
+            dir_point.x,
+            dir_point.y,
+            gs.player_weapon.bump_attack,
+        );
     }
 
     RunState::Running
 }
 
-fn handle_attack(gs: &mut State, data: AttackData) -> RunState {
+fn handle_attack(gs: &mut State, data: AttackData, source: AttackSource) -> RunState {
     let mut attacks = gs.ecs.write_storage::<AttackIntent>();
     let mut frames = gs.ecs.write_storage::<FrameData>();
     let positions = gs.ecs.read_storage::<Position>();
@@ -150,6 +202,7 @@ fn handle_attack(gs: &mut State, data: AttackData) -> RunState {
     if data.needs_target {
         return RunState::Targetting {
             attack_type: data.attack_type,
+            source,
             cursor_point: pos.as_point(),
             validity_mode: TargettingValid::All,
             show_path: data.needs_path,
@@ -162,12 +215,16 @@ fn handle_attack(gs: &mut State, data: AttackData) -> RunState {
     let intent = AttackIntent {
         main: data.attack_type,
         loc: pos.as_point(),
+        bonus: data.bonus,
     };
     attacks
         .insert(*player, intent)
         .expect("Failed to insert new attack from player");
     frames
-        .insert(*player, attack_type::get_frame_data(data.attack_type))
+        .insert(
+            *player,
+            attack_type::get_bonus_frame_data(data.attack_type, data.bonus),
+        )
         .ok();
 
     RunState::Running
@@ -213,15 +270,16 @@ fn handle_charging(gs: &mut State) -> bool {
         // If the obstacle happens to be a creature, also put in an attack (bump?)
         if let Some(_dest_ent) = map.creature_map.get(&dest_index) {
             let attack = AttackIntent {
-                main: AttackType::Melee,
+                main: AttackType::builtin("melee"),
                 loc: next_point,
+                bonus: AttackBonus::default(),
             };
 
             attacks
                 .insert(*player, attack)
                 .expect("Failed to insert new attack from player");
             frames
-                .insert(*player, get_frame_data(AttackType::Melee))
+                .insert(*player, get_frame_data(AttackType::builtin("melee")))
                 .ok();
 
             return false;
@@ -256,63 +314,420 @@ pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
             .expect("player_input called, but it is not your turn");
     };
 
-    if gs.player_charging.0 {
-        // check bool that auto-movement only happens once
-        if !gs.player_charging.3 {
-            let can_player_take_action = handle_charging(gs);
-
-            if !can_player_take_action {
-                return RunState::Running;
-            } else {
-                // process the movement once now before handling player input
-                sys_movement::MovementSystem.run_now(&gs.ecs);
-                gs.player_charging.3 = true;
-            }
-        }
+    if let Some(next_state) = start_turn(gs) {
+        return next_state;
+    }
 
-        let next_state = match ctx.key {
+    if gs.playback.is_some() {
+        return match next_command(gs, None) {
+            Some(command) => apply_command(gs, command),
             None => RunState::AwaitingInput,
+        };
+    }
+
+    if gs.player_charging.0 {
+        let command = match ctx.key {
+            None => return RunState::AwaitingInput,
             Some(key) => match key {
                 VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => {
-                    try_move_charging(gs, crate::Direction::W, gs.player_charging.1)
+                    PlayerCommand::Move {
+                        dir: crate::Direction::W,
+                    }
                 }
                 VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => {
-                    try_move_charging(gs, crate::Direction::E, gs.player_charging.1)
+                    PlayerCommand::Move {
+                        dir: crate::Direction::E,
+                    }
                 }
                 VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => {
-                    try_move_charging(gs, crate::Direction::N, gs.player_charging.1)
+                    PlayerCommand::Move {
+                        dir: crate::Direction::N,
+                    }
                 }
                 VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => {
-                    try_move_charging(gs, crate::Direction::S, gs.player_charging.1)
+                    PlayerCommand::Move {
+                        dir: crate::Direction::S,
+                    }
                 }
-                VirtualKeyCode::Period => RunState::Running,
-                _ => RunState::AwaitingInput,
+                VirtualKeyCode::Period => PlayerCommand::Wait,
+                _ => return RunState::AwaitingInput,
             },
         };
 
-        if next_state == RunState::Running {
-            gs.player_charging.3 = false;
+        apply_command(gs, command)
+    } else {
+        handle_keys(gs, ctx)
+    }
+}
 
-            // end charging if we run out of stamina
-            let mut stams = gs.ecs.write_storage::<Stamina>();
-            let player = gs.ecs.fetch::<Entity>();
-            let stamina = stams.get_mut(*player).unwrap();
+// anything that happens at the start of the player's turn, before a command is picked
+// returns a state if the turn ended before the player gets to act
+pub fn start_turn(gs: &mut State) -> Option<RunState> {
+    if gs.player_charging.0 {
+        start_charge_turn(gs)
+    } else {
+        None
+    }
+}
 
-            if stamina.current < HOOK_STAM_REQ {
-                gs.player_charging.0 = false;
-                return RunState::Running;
+// anything the player is standing on goes into their bag, if there is room
+pub fn pick_up_items(gs: &mut State) {
+    let player_point = {
+        let positions = gs.ecs.read_storage::<Position>();
+        let player = gs.ecs.fetch::<Entity>();
+        positions.get(*player).unwrap().as_point()
+    };
+
+    let item_ent = {
+        let map = gs.ecs.fetch::<Map>();
+        match map
+            .item_map
+            .get(&map.get_index(player_point.x, player_point.y))
+        {
+            None => return,
+            Some(item_ent) => *item_ent,
+        }
+    };
+
+    let name = match gs.ecs.read_storage::<Item>().get(item_ent) {
+        None => return,
+        Some(item) => item.name.clone(),
+    };
+
+    if gs.player_inventory.is_full() {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add(format!(
+            "There is a {} here, but your bag is full",
+            name.to_lowercase()
+        ));
+        return;
+    }
+
+    gs.ecs.fetch_mut::<Map>().untrack_item(player_point);
+    gs.ecs
+        .delete_entity(item_ent)
+        .expect("Failed to remove picked up item");
+
+    let mut log = gs.ecs.fetch_mut::<GameLog>();
+    log.add(format!("You pick up a {}", name.to_lowercase()));
+    gs.player_inventory.consumables.push(name);
+}
+
+// while watching a replay, commands come from the replay instead of the keyboard
+pub fn next_command(gs: &mut State, from_keys: Option<PlayerCommand>) -> Option<PlayerCommand> {
+    let playback = match &mut gs.playback {
+        None => return from_keys,
+        Some(playback) => playback,
+    };
+
+    let command = playback.next_command_when_ready(gs.tick);
+    let finished = playback.is_finished();
+    let desync = playback.desync.take();
+
+    let mut log = gs.ecs.fetch_mut::<GameLog>();
+    if let Some(desync) = desync {
+        log.add(desync);
+    }
+
+    if finished {
+        log.add("The replay has ended");
+        gs.playback = None;
+    }
+
+    command
+}
+
+// execute a command, whether it came from the keyboard, a script or a replay
+pub fn apply_command(gs: &mut State, command: PlayerCommand) -> RunState {
+    if gs.playback.is_none() {
+        if let Some(recording) = &mut gs.recording {
+            recording.record(gs.tick, command);
+        }
+    }
+
+    if command == PlayerCommand::Retry {
+        gs.load_overworld();
+        gs.reset_player();
+        return RunState::Running;
+    }
+
+    if gs.player_charging.0 {
+        return charge_command(gs, command);
+    }
+
+    let reacting = is_reacting(&gs.ecs);
+    if reacting && matches!(command, PlayerCommand::Move { .. }) {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add("There's no time to move, dodge or counter instead");
+        return RunState::AwaitingInput;
+    }
+
+    // waiting holds the guard up, anything else lowers it
+    if !matches!(command, PlayerCommand::Guard | PlayerCommand::Wait) {
+        let player = gs.ecs.fetch::<Entity>();
+        gs.ecs.write_storage::<BlockAttack>().remove(*player);
+    }
+
+    let next_state = match command {
+        PlayerCommand::Move { dir } => {
+            let offset = dir.to_point();
+            try_move_player(
+                &mut gs.ecs,
+                offset.x,
+                offset.y,
+                gs.player_weapon.bump_attack,
+            )
+        }
+        PlayerCommand::Wait => RunState::Running,
+        PlayerCommand::Dodge => dodge(gs),
+        PlayerCommand::Guard => guard(gs),
+        PlayerCommand::Ability { index, target } => use_ability(gs, index, target),
+        PlayerCommand::UseItem { index, target } => use_item(gs, index, target),
+        PlayerCommand::Target {
+            attack_type,
+            source,
+            target,
+        } => {
+            confirm_target(gs, attack_type, source, target);
+            RunState::Running
+        }
+        PlayerCommand::Buy { index } => {
+            if gs.ecs.fetch::<Map>().level == 0 {
+                crate::shop::buy_item(gs, index);
+            }
+            RunState::AwaitingInput
+        }
+        PlayerCommand::EnterArena { level } => {
+            if gs.ecs.fetch::<Map>().level == 0 && level >= 1 && level <= gs.max_cleared_level + 1 {
+                RunState::ChangeMap { level }
             } else {
-                stamina.current -= HOOK_STAM_REQ;
-                stamina.recover = false;
+                RunState::AwaitingInput
+            }
+        }
+        PlayerCommand::TakeContract { contract } => {
+            if gs.ecs.fetch::<Map>().level == 0 {
+                gs.ecs.fetch_mut::<MissionInfo>().next_contract = Some(contract);
+                let mut log = gs.ecs.fetch_mut::<GameLog>();
+                log.add(format!("You take the {} contract", contract.name()));
+            }
+            RunState::AwaitingInput
+        }
+        PlayerCommand::Upgrade { upgrade } => {
+            if gs.ecs.fetch::<Map>().level == 0 {
+                crate::upgrade::buy_upgrade(gs, upgrade);
+            }
+            RunState::AwaitingInput
+        }
+        PlayerCommand::Equip { weapon_type } => {
+            if gs.ecs.fetch::<Map>().level == 0 {
+                gs.equip_weapon(weapon_type);
             }
+            RunState::AwaitingInput
         }
+        PlayerCommand::Retry => unreachable!(),
+    };
 
-        next_state
-    } else {
-        handle_keys(gs, ctx)
+    // acting in a reaction window costs the next turn, letting it pass is free
+    if reacting && next_state == RunState::Running && command != PlayerCommand::Wait {
+        let player = gs.ecs.fetch::<Entity>();
+        let mut schedulables = gs.ecs.write_storage::<Schedulable>();
+        if let Some(sched) = schedulables.get_mut(*player) {
+            sched.current += sched.base;
+        }
+    }
+
+    next_state
+}
+
+pub fn is_reacting(ecs: &World) -> bool {
+    let can_act = ecs.read_storage::<CanActFlag>();
+    let player = ecs.fetch::<Entity>();
+    can_act
+        .get(*player)
+        .is_some_and(|can_act| can_act.is_reaction)
+}
+
+fn start_charge_turn(gs: &mut State) -> Option<RunState> {
+    // check bool that auto-movement only happens once
+    if !gs.player_charging.3 {
+        let can_player_take_action = handle_charging(gs);
+
+        if !can_player_take_action {
+            return Some(RunState::Running);
+        } else {
+            // process the movement once now before handling player input
+            sys_movement::MovementSystem.run_now(&gs.ecs);
+            gs.player_charging.3 = true;
+        }
+    }
+
+    None
+}
+
+fn charge_command(gs: &mut State, command: PlayerCommand) -> RunState {
+    let next_state = match command {
+        PlayerCommand::Move { dir } => try_move_charging(gs, dir, gs.player_charging.1),
+        _ => RunState::Running,
+    };
+
+    if next_state == RunState::Running {
+        gs.player_charging.3 = false;
+
+        // end charging if we run out of stamina
+        let mut stams = gs.ecs.write_storage::<Stamina>();
+        let player = gs.ecs.fetch::<Entity>();
+        let stamina = stams.get_mut(*player).unwrap();
+
+        if stamina.current < HOOK_STAM_REQ {
+            gs.player_charging.0 = false;
+            return RunState::Running;
+        } else {
+            stamina.current -= HOOK_STAM_REQ;
+            stamina.recover = false;
+        }
+    }
+
+    next_state
+}
+
+fn use_ability(gs: &mut State, index: usize, target: Option<Point>) -> RunState {
+    let data = match gs.player_weapon.abilities.get(index) {
+        Some(data) => data.clone(),
+        None => {
+            let mut log = gs.ecs.fetch_mut::<GameLog>();
+            log.add("You don't know that ability");
+            return RunState::AwaitingInput;
+        }
+    };
+
+    attack_with(gs, data, AttackSource::Ability { index }, target)
+}
+
+// items are used like abilities, so they take a turn and can be interrupted
+fn use_item(gs: &mut State, index: usize, target: Option<Point>) -> RunState {
+    let info = match gs.player_inventory.consumables.get(index) {
+        Some(name) => crate::data::item_info::get_item_info(name),
+        None => None,
+    };
+
+    let info = match info {
+        Some(info) => info,
+        None => {
+            let mut log = gs.ecs.fetch_mut::<GameLog>();
+            log.add("You don't have that item");
+            return RunState::AwaitingInput;
+        }
+    };
+
+    let data = AttackData {
+        needs_target: get_attack_range(info.attack) != RangeType::Single,
+        needs_path: get_attack_traits(info.attack)
+            .iter()
+            .any(|tr| matches!(tr, AttackTrait::FollowsPath { .. })),
+        name: info.name.clone(),
+        stam_cost: 0,
+        attack_type: info.attack,
+        bonus: AttackBonus::default(),
+    };
+
+    let next_state = attack_with(gs, data, AttackSource::Item { index }, target);
+    if next_state == RunState::Running && get_attack_range(info.attack) == RangeType::Single {
+        consume_item(gs, index);
+    }
+
+    next_state
+}
+
+// use up a carried item, by its place in the inventory
+fn consume_item(gs: &mut State, index: usize) {
+    let consumables = &mut gs.player_inventory.consumables;
+    if index < consumables.len() {
+        let name = consumables.remove(index);
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add(format!("You use a {}", name.to_lowercase()));
+
+        let mut mission = gs.ecs.fetch_mut::<MissionInfo>();
+        let heals = crate::data::item_info::get_item_info(&name).is_some_and(|info| {
+            get_attack_traits(info.attack)
+                .iter()
+                .any(|tr| matches!(tr, AttackTrait::Heal { .. }))
+        });
+        if heals && mission.contract == Some(crate::Contract::NoPotions) && !mission.failed {
+            mission.failed = true;
+            log.add("You broke the terms of the contract");
+        }
+    }
+}
+
+fn attack_with(
+    gs: &mut State,
+    data: AttackData,
+    source: AttackSource,
+    target: Option<Point>,
+) -> RunState {
+    match handle_attack(gs, data, source) {
+        // without a target, let the player pick one
+        next_state @ RunState::Targetting { .. } if target.is_none() => next_state,
+        RunState::Targetting { attack_type, .. } => {
+            let player_point = {
+                let positions = gs.ecs.read_storage::<Position>();
+                let player = gs.ecs.fetch::<Entity>();
+                positions.get(*player).unwrap().as_point()
+            };
+            let range_type = crate::attack_type::get_attack_range(attack_type);
+            let in_range = target.filter(|target| {
+                crate::range_type::resolve_range_at(&range_type, player_point).contains(target)
+            });
+
+            match in_range {
+                Some(target) => {
+                    confirm_target(gs, attack_type, source, target);
+                    RunState::Running
+                }
+                None => {
+                    let mut log = gs.ecs.fetch_mut::<GameLog>();
+                    log.add("Invalid Target");
+                    RunState::AwaitingInput
+                }
+            }
+        }
+        next_state => next_state,
     }
 }
 
+// queue up a targeted attack once a target has been picked
+pub fn confirm_target(
+    gs: &mut State,
+    attack_type: AttackType,
+    source: AttackSource,
+    target: Point,
+) {
+    // abilities carry their upgrades, while items are used up now that they have a target
+    let bonus = match source {
+        AttackSource::Ability { index } => gs
+            .player_weapon
+            .abilities
+            .get(index)
+            .map_or(AttackBonus::default(), |ability| ability.bonus),
+        AttackSource::Item { index } => {
+            consume_item(gs, index);
+            AttackBonus::default()
+        }
+    };
+
+    let mut attacks = gs.ecs.write_storage::<AttackIntent>();
+    let mut frames = gs.ecs.write_storage::<FrameData>();
+    let player = gs.ecs.fetch::<Entity>();
+
+    let mut intent = get_attack_intent(attack_type, target, None);
+    intent.bonus = bonus;
+    attacks.insert(*player, intent).ok();
+
+    frames
+        .insert(*player, get_bonus_frame_data(attack_type, bonus))
+        .ok();
+}
+
 fn handle_dodge(ecs: &mut World) -> Option<MoveIntent> {
     let player = ecs.fetch::<Entity>();
     let map = ecs.fetch::<Map>();
@@ -362,17 +777,77 @@ pub fn can_dodge(gs: &State) -> bool {
     let stams = gs.ecs.read_storage::<Stamina>();
     let player = gs.ecs.fetch::<Entity>();
     let stamina = stams.get(*player).unwrap();
-    stamina.current >= DODGE_STAM_REQ
+    stamina.current >= gs.player_weapon.dodge_cost
 }
 
-fn reduce_stam_for_dodge(ecs: &mut World) {
+fn reduce_stam_for_dodge(ecs: &mut World, cost: i32) {
     let mut stams = ecs.write_storage::<Stamina>();
     let player = ecs.fetch::<Entity>();
     let stamina = stams.get_mut(*player).unwrap();
-    stamina.current -= DODGE_STAM_REQ;
+    stamina.current -= cost;
     stamina.recover = false;
 }
 
+// hop backwards, ignoring hits while in the air
+fn dodge(gs: &mut State) -> RunState {
+    if !can_dodge(gs) {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add("You're too exhausted for that");
+        return RunState::AwaitingInput;
+    }
+
+    match handle_dodge(&mut gs.ecs) {
+        None => {
+            let mut log = gs.ecs.fetch_mut::<GameLog>();
+            log.add("There's no room to dodge");
+            RunState::AwaitingInput
+        }
+        Some(movement) => {
+            {
+                let mut movements = gs.ecs.write_storage::<MoveIntent>();
+                let player = gs.ecs.fetch::<Entity>();
+                movements
+                    .insert(*player, movement)
+                    .expect("Failed to insert new movement from player");
+            }
+
+            reduce_stam_for_dodge(&mut gs.ecs, gs.player_weapon.dodge_cost);
+            apply_invuln(&mut gs.ecs);
+            RunState::Running
+        }
+    }
+}
+
+// raising the guard costs as much as a block, so parries can't be had for free
+fn guard(gs: &mut State) -> RunState {
+    let player = gs.ecs.fetch::<Entity>();
+    let mut stams = gs.ecs.write_storage::<Stamina>();
+    let stamina = stams.get_mut(*player).unwrap();
+    if stamina.current < crate::consts::BLOCK_STAMINA_COST {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add("You're too exhausted for that");
+        return RunState::AwaitingInput;
+    }
+
+    stamina.current -= crate::consts::BLOCK_STAMINA_COST;
+    stamina.recover = false;
+
+    let mut blocks = gs.ecs.write_storage::<BlockAttack>();
+    blocks
+        .insert(
+            *player,
+            BlockAttack {
+                block_amount: crate::consts::PLAYER_BLOCK_AMOUNT,
+                parry_window: crate::consts::PARRY_WINDOW,
+            },
+        )
+        .expect("Failed to insert BlockAttack");
+
+    let mut log = gs.ecs.fetch_mut::<GameLog>();
+    log.add("You raise your guard");
+    RunState::Running
+}
+
 pub fn end_turn_cleanup(ecs: &mut World) {
     // remove can act flag
     // let player = ecs.fetch::<Entity>();
@@ -395,23 +870,39 @@ fn handle_keys(gs: &mut State, ctx: &mut Rltk) -> RunState {
     match ctx.key {
         None => RunState::AwaitingInput,
         Some(key) => match key {
-            VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => {
-                let next_state = try_move_player(&mut gs.ecs, -1, 0);
-                next_state
-            }
-            VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => {
-                let next_state = try_move_player(&mut gs.ecs, 1, 0);
-                next_state
-            }
-            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => {
-                let next_state = try_move_player(&mut gs.ecs, 0, -1);
-                next_state
+            VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => apply_command(
+                gs,
+                PlayerCommand::Move {
+                    dir: crate::Direction::W,
+                },
+            ),
+            VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => apply_command(
+                gs,
+                PlayerCommand::Move {
+                    dir: crate::Direction::E,
+                },
+            ),
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => apply_command(
+                gs,
+                PlayerCommand::Move {
+                    dir: crate::Direction::N,
+                },
+            ),
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => apply_command(
+                gs,
+                PlayerCommand::Move {
+                    dir: crate::Direction::S,
+                },
+            ),
+            VirtualKeyCode::Period | VirtualKeyCode::Numpad5 => {
+                apply_command(gs, PlayerCommand::Wait)
             }
-            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => {
-                let next_state = try_move_player(&mut gs.ecs, 0, 1);
-                next_state
+            VirtualKeyCode::Space => apply_command(gs, PlayerCommand::Dodge),
+            VirtualKeyCode::G => apply_command(gs, PlayerCommand::Guard),
+            VirtualKeyCode::D => {
+                gs.show_danger = !gs.show_danger;
+                RunState::AwaitingInput
             }
-            VirtualKeyCode::Period | VirtualKeyCode::Numpad5 => RunState::Running,
             // VirtualKeyCode::P => {
             //     gs.spawn_exit();
             //     RunState::AwaitingInput
@@ -423,8 +914,37 @@ fn handle_keys(gs: &mut State, ctx: &mut Rltk) -> RunState {
             // }
             // VirtualKeyCode::V => RunState::ViewEnemy { index: 0 },
             VirtualKeyCode::A => RunState::AbilitySelect { index: 0 },
-            // VirtualKeyCode::I => RunState::InventorySelect { index: 0 },
+            VirtualKeyCode::W => {
+                if gs.ecs.fetch::<Map>().level == 0 {
+                    RunState::WeaponSelect { index: 0 }
+                } else {
+                    let mut log = gs.ecs.fetch_mut::<GameLog>();
+                    log.add("You can only change weapons at the base");
+                    RunState::AwaitingInput
+                }
+            }
+            VirtualKeyCode::I => RunState::InventorySelect { index: 0 },
             VirtualKeyCode::V => RunState::ViewGameLog,
+            VirtualKeyCode::S => {
+                let message = match crate::saveload::save_game(gs) {
+                    Ok(()) => "Game saved".to_string(),
+                    Err(e) => format!("Unable to save: {}", e),
+                };
+                gs.ecs.fetch_mut::<GameLog>().add(message);
+                RunState::AwaitingInput
+            }
+            VirtualKeyCode::R => {
+                let result = match &gs.recording {
+                    Some(recording) => recording.write(crate::replay::REPLAY_NAME),
+                    None => Err("There is nothing to record".into()),
+                };
+                let message = match result {
+                    Ok(()) => format!("Replay written to {}", crate::replay::REPLAY_NAME),
+                    Err(e) => format!("Unable to write replay: {}", e),
+                };
+                gs.ecs.fetch_mut::<GameLog>().add(message);
+                RunState::AwaitingInput
+            }
             _ => RunState::AwaitingInput,
         },
     }
@@ -445,6 +965,19 @@ pub enum SelectionResult {
     NoResponse,
 }
 
+// targets come from the replay instead of the cursor. If the next command isn't a target, the
+// player backed out of targeting when it was recorded
+pub fn replay_target(gs: &mut State) -> (SelectionResult, Option<Point>) {
+    let peeked = gs.playback.as_ref().and_then(|playback| playback.peek());
+    match peeked {
+        Some(PlayerCommand::Target { .. }) => match next_command(gs, None) {
+            Some(PlayerCommand::Target { target, .. }) => (SelectionResult::Selected, Some(target)),
+            _ => (SelectionResult::NoResponse, None),
+        },
+        _ => (SelectionResult::Canceled, None),
+    }
+}
+
 pub fn ranged_target(
     gs: &mut State,
     ctx: &mut Rltk,
@@ -670,7 +1203,7 @@ pub fn view_input(gs: &mut State, ctx: &mut Rltk, index: u32) -> RunState {
 
 pub fn ability_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
     let mut new_index = index;
-    let max_index = gs.player_abilities.len();
+    let max_index = gs.player_weapon.abilities.len();
 
     if ctx.shift && matches!(ctx.key, Some(key) if key == VirtualKeyCode::Q) {
         return RunState::AwaitingInput;
@@ -693,12 +1226,24 @@ pub fn ability_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> Run
                 return RunState::AwaitingInput;
             }
             VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
-                return handle_attack(gs, gs.player_abilities[index].clone())
+                return apply_command(
+                    gs,
+                    PlayerCommand::Ability {
+                        index,
+                        target: None,
+                    },
+                );
             }
             _ => {
                 let selection = rltk::letter_to_option(key) as usize;
                 if selection < max_index {
-                    return handle_attack(gs, gs.player_abilities[selection].clone());
+                    return apply_command(
+                        gs,
+                        PlayerCommand::Ability {
+                            index: selection,
+                            target: None,
+                        },
+                    );
                 }
             }
         },
@@ -709,6 +1254,203 @@ pub fn ability_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> Run
     }
 }
 
+pub fn weapon_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
+    let mut new_index = index;
+    let max_index = crate::weapon::WEAPON_TYPES.len();
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => {
+                if new_index > 0 {
+                    new_index -= 1;
+                } else {
+                    new_index += max_index - 1;
+                }
+            }
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
+                new_index += 1;
+            }
+            VirtualKeyCode::Escape | VirtualKeyCode::Back => {
+                return RunState::AwaitingInput;
+            }
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                let weapon_type = crate::weapon::WEAPON_TYPES[index];
+                return apply_command(gs, PlayerCommand::Equip { weapon_type });
+            }
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+                if selection < max_index {
+                    let weapon_type = crate::weapon::WEAPON_TYPES[selection];
+                    return apply_command(gs, PlayerCommand::Equip { weapon_type });
+                }
+            }
+        },
+    }
+
+    RunState::WeaponSelect {
+        index: new_index % max_index,
+    }
+}
+
+pub fn upgrade_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
+    let upgrades = crate::upgrade::get_upgrades(&gs.player_weapon);
+    let mut new_index = index;
+    let max_index = upgrades.len();
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => {
+                if new_index > 0 {
+                    new_index -= 1;
+                } else {
+                    new_index += max_index - 1;
+                }
+            }
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
+                new_index += 1;
+            }
+            VirtualKeyCode::Escape | VirtualKeyCode::Back => {
+                return RunState::AwaitingInput;
+            }
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                let upgrade = upgrades[index];
+                apply_command(gs, PlayerCommand::Upgrade { upgrade });
+            }
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+                if selection < max_index {
+                    let upgrade = upgrades[selection];
+                    apply_command(gs, PlayerCommand::Upgrade { upgrade });
+                    new_index = selection;
+                }
+            }
+        },
+    }
+
+    // stay at the blacksmith so several upgrades can be bought in a row
+    RunState::UpgradeSelect {
+        index: new_index % max_index,
+    }
+}
+
+pub fn shop_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
+    let mut new_index = index;
+    let max_index = crate::shop::get_stock().len();
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => {
+                if new_index > 0 {
+                    new_index -= 1;
+                } else {
+                    new_index += max_index - 1;
+                }
+            }
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
+                new_index += 1;
+            }
+            VirtualKeyCode::Escape | VirtualKeyCode::Back => {
+                return RunState::AwaitingInput;
+            }
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                apply_command(gs, PlayerCommand::Buy { index });
+            }
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+                if selection < max_index {
+                    apply_command(gs, PlayerCommand::Buy { index: selection });
+                    new_index = selection;
+                }
+            }
+        },
+    }
+
+    RunState::ShopSelect {
+        index: new_index % max_index,
+    }
+}
+
+pub fn mission_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
+    let mut new_index = index;
+    let max_index = crate::mission_info::CONTRACT_OFFERS.len();
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => {
+                if new_index > 0 {
+                    new_index -= 1;
+                } else {
+                    new_index += max_index - 1;
+                }
+            }
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
+                new_index += 1;
+            }
+            VirtualKeyCode::Escape | VirtualKeyCode::Back => {
+                return RunState::AwaitingInput;
+            }
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                let contract = crate::mission_info::CONTRACT_OFFERS[index];
+                return apply_command(gs, PlayerCommand::TakeContract { contract });
+            }
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+                if selection < max_index {
+                    let contract = crate::mission_info::CONTRACT_OFFERS[selection];
+                    return apply_command(gs, PlayerCommand::TakeContract { contract });
+                }
+            }
+        },
+    }
+
+    RunState::MissionSelect {
+        index: new_index % max_index,
+    }
+}
+
+pub fn tier_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
+    let mut new_index = index;
+    let max_index = gs.max_cleared_level as usize + 1;
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 => {
+                if new_index > 0 {
+                    new_index -= 1;
+                } else {
+                    new_index += max_index - 1;
+                }
+            }
+            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
+                new_index += 1;
+            }
+            VirtualKeyCode::Escape | VirtualKeyCode::Back => {
+                return RunState::AwaitingInput;
+            }
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                let level = index as u32 + 1;
+                return apply_command(gs, PlayerCommand::EnterArena { level });
+            }
+            _ => {
+                let selection = rltk::letter_to_option(key) as usize;
+                if selection < max_index {
+                    let level = selection as u32 + 1;
+                    return apply_command(gs, PlayerCommand::EnterArena { level });
+                }
+            }
+        },
+    }
+
+    RunState::TierSelect {
+        index: new_index % max_index,
+    }
+}
+
 pub fn inventory_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> RunState {
     let mut new_index = index;
     let max_index = gs.player_inventory.consumables.len();
@@ -724,7 +1466,7 @@ pub fn inventory_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> R
                 if new_index > 0 {
                     new_index -= 1;
                 } else {
-                    new_index += max_index;
+                    new_index += max_index - 1;
                 }
             }
             VirtualKeyCode::Down | VirtualKeyCode::Numpad2 => {
@@ -734,12 +1476,24 @@ pub fn inventory_select_input(gs: &mut State, ctx: &mut Rltk, index: usize) -> R
                 return RunState::AwaitingInput;
             }
             VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
-                todo!();
+                return apply_command(
+                    gs,
+                    PlayerCommand::UseItem {
+                        index,
+                        target: None,
+                    },
+                );
             }
             _ => {
                 let selection = rltk::letter_to_option(key) as usize;
                 if selection < max_index {
-                    todo!();
+                    return apply_command(
+                        gs,
+                        PlayerCommand::UseItem {
+                            index: selection,
+                            target: None,
+                        },
+                    );
                 }
             }
         },
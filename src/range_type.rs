@@ -1,6 +1,7 @@
 use rltk::Point;
+use serde::Deserialize;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Deserialize)]
 pub enum RangeType {
     Empty,
     Single,
@@ -14,6 +15,34 @@ pub enum RangeType {
     Custom { offsets: Vec<(i32, i32)> },
 }
 
+impl RangeType {
+    // the same range with its size grown, ranges without a size are left as they are
+    pub fn grown(&self, amount: i32) -> RangeType {
+        let mut range = self.clone();
+        if let Some(size) = range.size_mut() {
+            *size += amount;
+        }
+
+        range
+    }
+
+    pub fn has_size(&self) -> bool {
+        self.clone().size_mut().is_some()
+    }
+
+    fn size_mut(&mut self) -> Option<&mut i32> {
+        match self {
+            RangeType::Square { size }
+            | RangeType::SquareInclusive { size }
+            | RangeType::Ring { size }
+            | RangeType::Diamond { size }
+            | RangeType::Cross { size } => Some(size),
+            RangeType::Ray { len, .. } => Some(len),
+            _ => None,
+        }
+    }
+}
+
 pub fn resolve_range_at(range: &RangeType, center: Point) -> Vec<Point> {
     let mut targets = Vec::new();
 
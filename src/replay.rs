@@ -0,0 +1,146 @@
+use crate::player::PlayerCommand;
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub const REPLAY_NAME: &str = "replay.yaml";
+
+const DEFAULT_DELAY: u32 = 8;
+const MAX_DELAY: u32 = 64;
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub tick: i32,
+    pub command: PlayerCommand,
+}
+
+// Everything needed to play a run back exactly. The world rng and the map and spawn generators
+// are all reseeded from streams of the run seed, so the seed covers every rng in the run
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: RunSeed,
+    // the gate offers tiers based on this, so it has to match when playing back
+    #[serde(default)]
+    pub max_cleared_level: u32,
+    pub commands: Vec<RecordedCommand>,
+}
+
+impl Replay {
+    pub fn new(seed: RunSeed) -> Self {
+        Self {
+            seed,
+            max_cleared_level: 0,
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, tick: i32, command: PlayerCommand) {
+        self.commands.push(RecordedCommand { tick, command });
+    }
+
+    pub fn write(&self, name: &str) -> rltk::BError {
+        storage::write(name, &serde_yaml::to_string(self)?)
+    }
+
+    pub fn read(name: &str) -> rltk::BResult<Self> {
+        let text = storage::read(name).ok_or(format!("Unable to read replay {}", name))?;
+        Ok(serde_yaml::from_str(&text)?)
+    }
+}
+
+// the replay named by `--replay <file>` on the command line, if there is one
+pub fn replay_from_args(args: &[String]) -> rltk::BResult<Option<Replay>> {
+    match args.iter().position(|arg| arg == "--replay") {
+        None => Ok(None),
+        Some(index) => match args.get(index + 1) {
+            None => Err("--replay needs a file".into()),
+            Some(name) => Replay::read(name).map(Some),
+        },
+    }
+}
+
+// Feeds recorded commands back in whenever the player can act
+pub struct Playback {
+    commands: VecDeque<RecordedCommand>,
+    check_ticks: bool,
+    played: usize,
+    pub desync: Option<String>,
+    // frames to wait before each command, lower is faster
+    pub delay: u32,
+    frames_waited: u32,
+}
+
+impl Playback {
+    pub fn from_replay(replay: &Replay) -> Self {
+        Self {
+            commands: replay.commands.iter().copied().collect(),
+            check_ticks: true,
+            played: 0,
+            desync: None,
+            delay: DEFAULT_DELAY,
+            frames_waited: 0,
+        }
+    }
+
+    // commands without ticks, e.g. from a script, are played back as soon as possible
+    pub fn from_commands(commands: &[PlayerCommand]) -> Self {
+        Self {
+            commands: commands
+                .iter()
+                .map(|command| RecordedCommand {
+                    tick: 0,
+                    command: *command,
+                })
+                .collect(),
+            check_ticks: false,
+            played: 0,
+            desync: None,
+            delay: 0,
+            frames_waited: 0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<PlayerCommand> {
+        self.commands.front().map(|recorded| recorded.command)
+    }
+
+    pub fn speed_up(&mut self) {
+        self.delay /= 2;
+    }
+
+    pub fn slow_down(&mut self) {
+        self.delay = (self.delay * 2).clamp(1, MAX_DELAY);
+    }
+
+    // waits out the delay before handing over the next command
+    pub fn next_command_when_ready(&mut self, tick: i32) -> Option<PlayerCommand> {
+        if self.frames_waited < self.delay {
+            self.frames_waited += 1;
+            return None;
+        }
+
+        self.frames_waited = 0;
+        self.next_command(tick)
+    }
+
+    pub fn next_command(&mut self, tick: i32) -> Option<PlayerCommand> {
+        let recorded = self.commands.pop_front()?;
+
+        // the first mismatch is the interesting one, anything after it is expected to drift
+        if self.check_ticks && recorded.tick != tick && self.desync.is_none() {
+            self.desync = Some(format!(
+                "Replay desynced at command {}: recorded on tick {}, played on tick {}",
+                self.played + 1,
+                recorded.tick,
+                tick
+            ));
+        }
+
+        self.played += 1;
+        Some(recorded.command)
+    }
+}
@@ -0,0 +1,87 @@
+use rltk::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+
+// Independent purposes that draw random numbers. Each gets its own stream so that,
+// for example, an extra roll during map generation doesn't shift every spawn after it
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RngStream {
+    MapGen,
+    Spawn,
+    World,
+}
+
+// The seed for a whole run. Every random number in the game is drawn from a stream derived
+// from it, so the same seed and the same inputs always play out the same way
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RunSeed {
+    pub seed: u64,
+    pub levels_generated: u32,
+}
+
+impl RunSeed {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            levels_generated: 0,
+        }
+    }
+
+    // random seeds are kept short so they are easy to read off the sidebar and type back in
+    pub fn random() -> Self {
+        Self::new(RandomNumberGenerator::new().next_u64() >> 32)
+    }
+
+    // the overworld and arenas can be revisited, so levels are counted rather than keyed by depth
+    pub fn next_level(&mut self) {
+        self.levels_generated += 1;
+    }
+
+    pub fn stream(&self, stream: RngStream) -> RandomNumberGenerator {
+        let stream_id = match stream {
+            RngStream::MapGen => 1,
+            RngStream::Spawn => 2,
+            RngStream::World => 3,
+        };
+
+        let mixed = splitmix(self.seed ^ splitmix((self.levels_generated as u64) << 8 | stream_id));
+        RandomNumberGenerator::seeded(mixed)
+    }
+}
+
+// spreads out nearby inputs so that neighbouring streams are unrelated
+fn splitmix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// seeds can be given as a number on the command line with --seed, or as ?seed= in the page URL
+pub fn seed_from_args(args: &[String]) -> Result<Option<u64>, String> {
+    match args.iter().position(|arg| arg == "--seed") {
+        None => Ok(None),
+        Some(index) => match args.get(index + 1) {
+            None => Err("--seed needs a value".to_string()),
+            Some(value) => parse_seed(value).map(Some),
+        },
+    }
+}
+
+pub fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid seed {}", value))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn seed_from_url() -> Option<u64> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let params = web_sys::UrlSearchParams::new_with_str(&search).ok()?;
+    parse_seed(&params.get("seed")?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn seed_from_url() -> Option<u64> {
+    None
+}
@@ -0,0 +1,338 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use specs::saveload::{
+    ConvertSaveload, DeserializeComponents, Marker, MarkerAllocator, SerializeComponents,
+    SimpleMarker, SimpleMarkerAllocator,
+};
+use std::convert::Infallible;
+
+const SAVE_NAME: &str = "savegame.yaml";
+const PROGRESS_NAME: &str = "progress.yaml";
+
+// marks entities that are written to a save
+pub struct SerializeMe;
+
+// A save is a stream of yaml documents: first everything outside of the ecs storages, then one
+// document for each component type in the order listed here
+macro_rules! saved_components {
+    ($action:ident!($($args:tt)*)) => {
+        $action!(
+            $($args)*;
+            Position,
+            Renderable,
+            Player,
+            Viewshed,
+            CanActFlag,
+            CanReactFlag,
+            Schedulable,
+            BlocksTile,
+            BlocksVision,
+            Viewable,
+            ViewableIndex,
+            Health,
+            Stamina,
+            Armor,
+            Bounty,
+            AttackIntent,
+            MoveIntent,
+            PartMoveIntent,
+            Moveset,
+            AttackPath,
+            FrameData,
+            AttackInProgress,
+            BlockAttack,
+            CanBlock,
+            AiState,
+            TrapAiState,
+            Item,
+            Openable,
+            Fragile,
+            MultiTile,
+            Facing,
+            PushForce,
+            Npc,
+            Invulnerable,
+            Stunned,
+            StatusEffects,
+            MissionTarget,
+            Guardian,
+            Boss,
+            Squad
+        )
+    };
+}
+
+macro_rules! serialize_components {
+    ($ecs:expr, $serializer:expr; $($type:ty),*) => {{
+        let entities = $ecs.entities();
+        let markers = $ecs.read_storage::<SimpleMarker<SerializeMe>>();
+        $(
+            SerializeComponents::<Infallible, SimpleMarker<SerializeMe>>::serialize(
+                &($ecs.read_storage::<$type>(),),
+                &entities,
+                &markers,
+                &mut $serializer,
+            )?;
+        )*
+    }};
+}
+
+macro_rules! deserialize_components {
+    ($ecs:expr, $documents:expr; $($type:ty),*) => {{
+        let entities = $ecs.entities();
+        let mut markers = $ecs.write_storage::<SimpleMarker<SerializeMe>>();
+        let mut allocator = $ecs.write_resource::<SimpleMarkerAllocator<SerializeMe>>();
+        $(
+            DeserializeComponents::<Infallible, _>::deserialize(
+                &mut ($ecs.write_storage::<$type>(),),
+                &entities,
+                &mut markers,
+                &mut allocator,
+                $documents.next().ok_or("Save file is missing components")?,
+            )?;
+        )*
+    }};
+}
+
+// everything a run needs that doesn't live in a component
+#[derive(Serialize, Deserialize)]
+struct SavedState {
+    tick: i32,
+    map: Map,
+    log: gamelog::GameLog,
+    seed: RunSeed,
+    rng: rltk::RandomNumberGenerator,
+    player_inventory: inventory::Inventory,
+    player_charging: (bool, Direction, u8, bool),
+    player_weapon: weapon::Weapon,
+    max_cleared_level: u32,
+    recording: Option<replay::Replay>,
+    mission: MissionInfo,
+    area: AreaRun,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CanActFlagData<M> {
+    is_reaction: bool,
+    reaction_target: Option<M>,
+}
+
+impl<M: Marker + Serialize> ConvertSaveload<M> for CanActFlag {
+    type Data = CanActFlagData<M>;
+    type Error = Infallible;
+
+    fn convert_into<F>(&self, mut ids: F) -> Result<Self::Data, Self::Error>
+    where
+        F: FnMut(Entity) -> Option<M>,
+    {
+        Ok(CanActFlagData {
+            is_reaction: self.is_reaction,
+            reaction_target: self.reaction_target.and_then(&mut ids),
+        })
+    }
+
+    fn convert_from<F>(data: Self::Data, mut ids: F) -> Result<Self, Self::Error>
+    where
+        F: FnMut(M) -> Option<Entity>,
+    {
+        Ok(CanActFlag {
+            is_reaction: data.is_reaction,
+            reaction_target: data.reaction_target.and_then(&mut ids),
+        })
+    }
+}
+
+// unit structs would be written as null, which reads back as a missing component
+macro_rules! saveload_unit_components {
+    ($($type:ident),*) => {
+        $(
+            impl<M: Marker + Serialize> ConvertSaveload<M> for $type {
+                type Data = bool;
+                type Error = Infallible;
+
+                fn convert_into<F>(&self, _ids: F) -> Result<Self::Data, Self::Error>
+                where
+                    F: FnMut(Entity) -> Option<M>,
+                {
+                    Ok(true)
+                }
+
+                fn convert_from<F>(_data: Self::Data, _ids: F) -> Result<Self, Self::Error>
+                where
+                    F: FnMut(M) -> Option<Entity>,
+                {
+                    Ok($type)
+                }
+            }
+        )*
+    };
+}
+
+saveload_unit_components!(
+    Player,
+    CanReactFlag,
+    BlocksTile,
+    BlocksVision,
+    AttackInProgress,
+    Openable,
+    MissionTarget,
+    Guardian
+);
+
+pub fn register(ecs: &mut World) {
+    ecs.register::<SimpleMarker<SerializeMe>>();
+    ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+}
+
+pub fn save_game(gs: &mut State) -> rltk::BError {
+    mark_entities(&mut gs.ecs);
+
+    let state = SavedState {
+        tick: gs.tick,
+        map: (*gs.ecs.fetch::<Map>()).clone(),
+        log: (*gs.ecs.fetch::<gamelog::GameLog>()).clone(),
+        seed: *gs.ecs.fetch::<RunSeed>(),
+        rng: (*gs.ecs.fetch::<rltk::RandomNumberGenerator>()).clone(),
+        player_inventory: gs.player_inventory.clone(),
+        player_charging: gs.player_charging,
+        player_weapon: gs.player_weapon.clone(),
+        max_cleared_level: gs.max_cleared_level,
+        recording: gs.recording.clone(),
+        mission: (*gs.ecs.fetch::<MissionInfo>()).clone(),
+        area: (*gs.ecs.fetch::<AreaRun>()).clone(),
+    };
+
+    // entities are listed up front so they can be recreated in the same order, which keeps
+    // systems visiting them (and drawing random numbers) in the same order after loading
+    let entity_order: Vec<SimpleMarker<SerializeMe>> = {
+        let markers = gs.ecs.read_storage::<SimpleMarker<SerializeMe>>();
+        markers.join().cloned().collect()
+    };
+
+    let mut writer = Vec::new();
+    {
+        let mut serializer = serde_yaml::Serializer::new(&mut writer);
+        state.serialize(&mut serializer)?;
+        entity_order.serialize(&mut serializer)?;
+        saved_components!(serialize_components!(gs.ecs, serializer));
+    }
+
+    storage::write(SAVE_NAME, &String::from_utf8(writer)?)
+}
+
+// returns false if there was no save to load
+pub fn load_game(gs: &mut State) -> rltk::BResult<bool> {
+    let text = match storage::read(SAVE_NAME) {
+        None => return Ok(false),
+        Some(text) => text,
+    };
+
+    let mut documents = serde_yaml::Deserializer::from_str(&text);
+    let state = SavedState::deserialize(documents.next().ok_or("Save file is empty")?)?;
+    let entity_order = Vec::<SimpleMarker<SerializeMe>>::deserialize(
+        documents.next().ok_or("Save file is missing entities")?,
+    )?;
+
+    // start from an empty world, since a reused one hands out entity ids in a different order
+    gs.ecs = World::new();
+    gs.register_components();
+    gs.ecs.insert(sys_particle::ParticleBuilder::new());
+    gs.ecs.insert(sys_spawner::Spawner::new());
+    gs.ecs.insert(sys_ai::Noises::new());
+    gs.ecs.insert(inventory::Earnings::default());
+
+    {
+        let entities = gs.ecs.entities();
+        let mut markers = gs.ecs.write_storage::<SimpleMarker<SerializeMe>>();
+        let mut allocator = gs
+            .ecs
+            .write_resource::<SimpleMarkerAllocator<SerializeMe>>();
+        for marker in entity_order {
+            allocator.retrieve_entity(marker, &mut markers, &entities);
+        }
+    }
+    saved_components!(deserialize_components!(gs.ecs, documents));
+
+    let player = {
+        let entities = gs.ecs.entities();
+        let players = gs.ecs.read_storage::<Player>();
+        (&entities, &players)
+            .join()
+            .map(|(entity, _)| entity)
+            .next()
+            .ok_or("Save file has no player")?
+    };
+
+    gs.tick = state.tick;
+    gs.player_inventory = state.player_inventory;
+    gs.player_charging = state.player_charging;
+    gs.player_weapon = state.player_weapon;
+    gs.max_cleared_level = std::cmp::max(gs.max_cleared_level, state.max_cleared_level);
+    gs.recording = state.recording;
+
+    gs.ecs.insert(player);
+    gs.ecs.insert(state.map);
+    gs.ecs.insert(state.log);
+    gs.ecs.insert(state.seed);
+    gs.ecs.insert(state.rng);
+    gs.ecs.insert(state.mission);
+    gs.ecs.insert(state.area);
+    gs.ecs.insert(RunState::AwaitingInput);
+
+    reindex_map(&mut gs.ecs);
+    storage::delete(SAVE_NAME);
+
+    Ok(true)
+}
+
+// Progress on the arena ladder outlives any single run, so it is kept apart from the save
+#[derive(Serialize, Deserialize)]
+struct Progress {
+    max_cleared_level: u32,
+}
+
+pub fn save_progress(max_cleared_level: u32) -> rltk::BError {
+    let progress = Progress { max_cleared_level };
+    storage::write(PROGRESS_NAME, &serde_yaml::to_string(&progress)?)
+}
+
+// a missing or unreadable file means starting from the bottom of the ladder
+pub fn load_progress() -> u32 {
+    storage::read(PROGRESS_NAME)
+        .and_then(|text| serde_yaml::from_str::<Progress>(&text).ok())
+        .map_or(0, |progress| progress.max_cleared_level)
+}
+
+// only the state of the arena is saved, particles are left to expire
+fn mark_entities(ecs: &mut World) {
+    let entities = ecs.entities();
+    let particles = ecs.read_storage::<ParticleLifetime>();
+    let mut markers = ecs.write_storage::<SimpleMarker<SerializeMe>>();
+    let mut allocator = ecs.write_resource::<SimpleMarkerAllocator<SerializeMe>>();
+
+    for (entity, _) in (&entities, !&particles).join() {
+        allocator.mark(entity, &mut markers);
+    }
+}
+
+fn reindex_map(ecs: &mut World) {
+    {
+        let entities = ecs.entities();
+        let items = ecs.read_storage::<Item>();
+        let positions = ecs.read_storage::<Position>();
+        let mut map = ecs.fetch_mut::<Map>();
+
+        for (entity, _, pos) in (&entities, &items, &positions).join() {
+            let index = map.get_index(pos.x, pos.y);
+            map.track_item(entity, index);
+        }
+
+        let targets = ecs.read_storage::<MissionTarget>();
+        let mut mission = ecs.fetch_mut::<MissionInfo>();
+        for (entity, _) in (&entities, &targets).join() {
+            mission.add(entity);
+        }
+    }
+
+    sys_mapindex::MapIndexSystem.run_now(ecs);
+}
@@ -0,0 +1,29 @@
+use crate::data::item_info::{self, ItemInfo};
+use crate::*;
+
+// the shopkeeper sells everything in data/items.yaml
+pub fn get_stock() -> &'static [ItemInfo] {
+    item_info::get_items()
+}
+
+pub fn buy_item(gs: &mut State, index: usize) {
+    let item = match get_stock().get(index) {
+        None => return,
+        Some(item) => item,
+    };
+
+    let mut log = gs.ecs.fetch_mut::<GameLog>();
+    if gs.player_inventory.money < item.price {
+        log.add("You can't afford that");
+        return;
+    }
+
+    if gs.player_inventory.is_full() {
+        log.add("You have no room for that");
+        return;
+    }
+
+    gs.player_inventory.money -= item.price;
+    gs.player_inventory.consumables.push(item.name.clone());
+    log.add(format!("You buy a {}", item.name.to_lowercase()));
+}
@@ -1,4 +1,3 @@
-pub mod melee;
-pub mod ranged;
+pub mod monster;
 pub mod spawner;
 pub mod traps;
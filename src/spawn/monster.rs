@@ -0,0 +1,84 @@
+use super::spawner::build_enemy_base;
+use crate::data::boss_info::BossInfo;
+use crate::data::monster_info::MonsterInfo;
+use crate::*;
+use rltk::Point;
+
+pub fn build_monster(ecs: &mut World, info: &MonsterInfo, point: Point) -> Entity {
+    let mut builder = build_enemy_base(ecs, info.speed, info.vision);
+    if info.reacts {
+        builder = builder.with(CanReactFlag);
+    }
+    if info.block > 0 {
+        builder = builder.with(CanBlock {
+            block_amount: info.block,
+        });
+    }
+
+    builder
+        .with(Position {
+            x: point.x,
+            y: point.y,
+        })
+        .with(Renderable {
+            symbol: info.glyph,
+            fg: info.color,
+            bg: bg_color(),
+            zindex: 1,
+        })
+        .with(Viewable {
+            name: info.name.clone(),
+            description: info.description.clone(),
+            seen: false,
+        })
+        .with(Health {
+            current: info.health,
+            max: info.health,
+        })
+        .with(Moveset {
+            moves: info.moves.clone(),
+            bump_attack: info.bump_attack,
+        })
+        .with(Bounty {
+            money: info.tier as u32 * crate::consts::MONEY_PER_TIER,
+        })
+        .build()
+}
+
+// the core is built like any other monster, with the parts' attacks added to its own
+pub fn build_boss(ecs: &mut World, info: &BossInfo, point: Point) -> Entity {
+    let mut monster = info.monster.clone();
+    for part in &info.parts {
+        monster.moves.extend(part.moves.iter().copied());
+    }
+
+    let part_list: Vec<MonsterPart> = info
+        .parts
+        .iter()
+        .map(|part| MonsterPart {
+            symbol_map: part.tiles.iter().map(|tile| (*tile, part.glyph)).collect(),
+            health: part.health,
+            max_health: part.health,
+            name: part.name.clone(),
+            moves: part.moves.clone(),
+            loot: part.loot.clone(),
+            broken: false,
+        })
+        .collect();
+
+    let entity = build_monster(ecs, &monster, point);
+    ecs.write_storage::<MultiTile>()
+        .insert(
+            entity,
+            MultiTile {
+                bounds: all_bounds(&part_list),
+                part_list,
+            },
+        )
+        .expect("Failed to insert boss parts");
+    ecs.write_storage::<Boss>()
+        .insert(entity, Boss)
+        .expect("Failed to insert boss");
+
+    entity
+}
@@ -1,108 +1,37 @@
+use crate::data::monster_info::MonsterInfo;
 use crate::*;
 use rltk::Point;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 const MAX_MONSTERS: i32 = 4;
 
 type Spawner = Box<for<'r> fn(&'r mut World, Point) -> Entity>;
 
 lazy_static! {
-    pub static ref MONSTERS: HashMap<String, (i32, Spawner)> = load_monster_table();
-    pub static ref DIFF_MAP: HashMap<i32, Vec<String>> = load_difficulty_map();
-    pub static ref ITEMS: HashMap<String, (i32, Spawner)> = load_item_table();
+    pub static ref MONSTERS: BTreeMap<String, MonsterInfo> = load_monster_table();
+    pub static ref DIFF_MAP: BTreeMap<i32, Vec<String>> = load_difficulty_map();
+    pub static ref ITEMS: BTreeMap<String, (i32, Spawner)> = load_item_table();
 }
 
-fn load_monster_table() -> HashMap<String, (i32, Spawner)> {
-    let mut table = HashMap::new();
-
-    table.insert(
-        "Archer".to_string(),
-        (
-            1,
-            Box::new(
-                super::ranged::build_archer
-                    as for<'r> fn(&'r mut specs::World, rltk::Point) -> specs::Entity,
-            ),
-        ),
-    );
-    table.insert(
-        "Sharpshooter".to_string(),
-        (2, Box::new(super::ranged::build_sharpshooter)),
-    );
-    table.insert(
-        "Cannoneer".to_string(),
-        (3, Box::new(super::ranged::build_cannoneer)),
-    );
-    table.insert(
-        "Novice".to_string(),
-        (1, Box::new(super::ranged::build_novice)),
-    );
-    table.insert(
-        "Electromancer".to_string(),
-        (2, Box::new(super::ranged::build_electromancer)),
-    );
-    table.insert(
-        "Pyromancer".to_string(),
-        (3, Box::new(super::ranged::build_pyromancer)),
-    );
-    // table.insert(
-    //     "Geomancer".to_string(),
-    //     (2, Box::new(super::ranged::build_geomancer)),
-    // );
-    table.insert(
-        "Trainee".to_string(),
-        (1, Box::new(super::melee::build_trainee)),
-    );
-    table.insert(
-        "Warrior".to_string(),
-        (3, Box::new(super::melee::build_warrior)),
-    );
-    table.insert(
-        "Berserker".to_string(),
-        (2, Box::new(super::melee::build_berserker)),
-    );
-    table.insert(
-        "Juggernaut".to_string(),
-        (4, Box::new(super::melee::build_juggernaut)),
-    );
-    table.insert(
-        "Assassin".to_string(),
-        (3, Box::new(super::melee::build_assassin)),
-    );
-
-    table
+fn load_monster_table() -> BTreeMap<String, MonsterInfo> {
+    crate::data::monster_info::get_monsters()
+        .iter()
+        .map(|info| (info.name.clone(), info.clone()))
+        .collect()
 }
 
-fn load_difficulty_map() -> HashMap<i32, Vec<String>> {
-    let mut table = HashMap::new();
-    let lvl1 = vec![
-        "Archer".to_string(),
-        "Novice".to_string(),
-        "Trainee".to_string(),
-    ];
-    let lvl2 = vec![
-        "Sharpshooter".to_string(),
-        "Electromancer".to_string(),
-        "Berserker".to_string(),
-    ];
-    let lvl3 = vec![
-        "Cannoneer".to_string(),
-        "Pyromancer".to_string(),
-        "Warrior".to_string(),
-        "Assassin".to_string(),
-    ];
-    let lvl4 = vec!["Juggernaut".to_string()];
-
-    table.insert(1, lvl1);
-    table.insert(2, lvl2);
-    table.insert(3, lvl3);
-    table.insert(4, lvl4);
+// tiers keep the order monsters are listed in the data file
+fn load_difficulty_map() -> BTreeMap<i32, Vec<String>> {
+    let mut table: BTreeMap<i32, Vec<String>> = BTreeMap::new();
+    for info in crate::data::monster_info::get_monsters() {
+        table.entry(info.tier).or_default().push(info.name.clone());
+    }
 
     table
 }
 
-fn load_item_table() -> HashMap<String, (i32, Spawner)> {
-    let mut table = HashMap::new();
+fn load_item_table() -> BTreeMap<String, (i32, Spawner)> {
+    let mut table = BTreeMap::new();
     table.insert(
         "Potion".to_string(),
         (
@@ -123,7 +52,9 @@ fn load_item_table() -> HashMap<String, (i32, Spawner)> {
 
 fn build_from_name(ecs: &mut World, name: &String, index: usize) -> Option<Entity> {
     let point = { ecs.fetch::<Map>().index_to_point2d(index) };
-    MONSTERS.get(name).map(|(_, builder)| builder(ecs, point))
+    MONSTERS
+        .get(name)
+        .map(|info| super::monster::build_monster(ecs, info, point))
 }
 
 fn build_item_from_name(ecs: &mut World, name: &String, index: usize) -> Option<Entity> {
@@ -131,14 +62,33 @@ fn build_item_from_name(ecs: &mut World, name: &String, index: usize) -> Option<
     ITEMS.get(name).map(|(_, builder)| builder(ecs, point))
 }
 
+// builds the items things dropped this turn, where there is room for them
+pub fn spawn_drops(ecs: &mut World) {
+    let drops = ecs.fetch_mut::<crate::Spawner>().take_drops();
+    for (point, name) in drops {
+        let index = ecs.fetch::<Map>().point2d_to_index(point);
+        if let Some(entity) = build_item_from_name(ecs, &name, index) {
+            // only one item fits on a tile
+            if !ecs.fetch_mut::<Map>().track_item(entity, index) {
+                ecs.delete_entity(entity).ok();
+            }
+        }
+    }
+}
+
 /// Fills a region with stuff!
-pub fn spawn_region(ecs: &mut World, area: &[usize], difficulty: i32) -> i32 {
-    let mut spawn_points: HashMap<usize, String> = HashMap::new();
+pub fn spawn_region(
+    ecs: &mut World,
+    rng: &mut rltk::RandomNumberGenerator,
+    area: &[usize],
+    difficulty: i32,
+    squad: i32,
+) -> i32 {
+    let mut spawn_points: BTreeMap<usize, String> = BTreeMap::new();
     let mut areas: Vec<usize> = Vec::from(area);
     let mut spawns = 0;
 
     {
-        let mut rng = ecs.fetch_mut::<rltk::RandomNumberGenerator>();
         let max_difficulty = difficulty;
         let mut curr_difficulty = 0;
 
@@ -147,7 +97,10 @@ pub fn spawn_region(ecs: &mut World, area: &[usize], difficulty: i32) -> i32 {
                 let mut valid_spawns = Vec::new();
 
                 for n in 1..=difficulty {
-                    let diff_spawns = DIFF_MAP.get(&n).unwrap();
+                    let diff_spawns = match DIFF_MAP.get(&n) {
+                        None => continue,
+                        Some(diff_spawns) => diff_spawns,
+                    };
                     for d in diff_spawns {
                         valid_spawns.push((d, n));
                     }
@@ -157,9 +110,8 @@ pub fn spawn_region(ecs: &mut World, area: &[usize], difficulty: i32) -> i32 {
                 *valid_spawns.get(rand_index).unwrap()
             } else {
                 let rand_index = rng.range(0, super::spawner::MONSTERS.len());
-                let (name, (difficulty, _)) =
-                    super::spawner::MONSTERS.iter().nth(rand_index).unwrap();
-                (name, *difficulty)
+                let (name, info) = super::spawner::MONSTERS.iter().nth(rand_index).unwrap();
+                (name, info.tier)
             };
 
             if areas.is_empty() {
@@ -189,19 +141,33 @@ pub fn spawn_region(ecs: &mut World, area: &[usize], difficulty: i32) -> i32 {
         if let Some(entity) = entity {
             track_entity(ecs, entity, *map_idx);
             spawns += 1;
+
+            ecs.write_storage::<Squad>()
+                .insert(entity, Squad { id: squad })
+                .expect("Failed to insert Squad");
+
+            if rng.rand::<f32>() < crate::consts::SLEEP_CHANCE {
+                if let Some(state) = ecs.write_storage::<AiState>().get_mut(entity) {
+                    state.status = Behavior::Sleep;
+                }
+            }
         }
     }
 
-    spawn_items(ecs, &mut areas, difficulty);
+    spawn_items(ecs, rng, &mut areas, difficulty);
 
     spawns
 }
 
-fn spawn_items(ecs: &mut World, areas: &mut Vec<usize>, difficulty: i32) {
-    let mut spawn_points: HashMap<usize, String> = HashMap::new();
+fn spawn_items(
+    ecs: &mut World,
+    rng: &mut rltk::RandomNumberGenerator,
+    areas: &mut Vec<usize>,
+    difficulty: i32,
+) {
+    let mut spawn_points: BTreeMap<usize, String> = BTreeMap::new();
 
     {
-        let mut rng = ecs.fetch_mut::<rltk::RandomNumberGenerator>();
         let item_chance = rng.rand::<f32>();
         for _ in 0..((difficulty + 1) / 2) {
             if item_chance < 0.3 {
@@ -236,6 +202,42 @@ fn spawn_items(ecs: &mut World, areas: &mut Vec<usize>, difficulty: i32) {
     }
 }
 
+// picks from the highest tier the difficulty allows
+pub fn build_guardian(
+    ecs: &mut World,
+    rng: &mut rltk::RandomNumberGenerator,
+    difficulty: i32,
+    point: Point,
+) -> Option<Entity> {
+    let (_, names) = DIFF_MAP.range(..=difficulty).next_back()?;
+    let name = &names[rng.range(0, names.len())];
+    let mut info = MONSTERS.get(name)?.clone();
+
+    let multiplier = crate::consts::GUARDIAN_MULTIPLIER;
+    info.health *= multiplier;
+    info.tier *= multiplier;
+    info.description
+        .push("Guards the way out of the area".to_string());
+
+    let entity = super::monster::build_monster(ecs, &info, point);
+    ecs.write_storage::<Guardian>()
+        .insert(entity, Guardian)
+        .expect("Failed to insert guardian");
+
+    Some(entity)
+}
+
+// bosses hold the end of an area the same way a guardian does
+pub fn build_boss(ecs: &mut World, name: &str, point: Point) -> Option<Entity> {
+    let info = crate::data::boss_info::get_boss_info(name)?;
+    let entity = super::monster::build_boss(ecs, info, point);
+    ecs.write_storage::<Guardian>()
+        .insert(entity, Guardian)
+        .expect("Failed to insert guardian");
+
+    Some(entity)
+}
+
 pub fn track_entity(ecs: &mut World, entity: Entity, map_idx: usize) {
     let mut map = ecs.fetch_mut::<Map>();
     let multis = ecs.read_storage::<MultiTile>();
@@ -290,24 +292,23 @@ pub fn build_player(ecs: &mut World, point: Point) -> Entity {
 // #endregion
 
 // #region Enemies
-pub fn build_enemy_base(ecs: &mut World) -> EntityBuilder {
+pub fn build_enemy_base(ecs: &mut World, speed: i32, vision: i32) -> EntityBuilder {
     ecs.create_entity()
         .with(ViewableIndex { list_index: None })
         .with(Schedulable {
             current: 0,
-            base: 6,
+            base: speed,
             delta: 1,
         })
         .with(Viewshed {
             visible: Vec::new(),
             dirty: true,
-            range: 10,
+            range: vision,
         })
         .with(BlocksTile)
         .with(AiState {
             status: Behavior::Wander,
-            prev_path: None,
-            path_step: 0,
+            has_fled: false,
         })
 }
 // #endregion
@@ -325,8 +326,9 @@ pub fn build_health_potion(ecs: &mut World, point: Point) -> Entity {
             bg: crate::bg_color(),
             zindex: 0,
         })
-        .with(Item)
-        .with(crate::Heal { amount: 4 })
+        .with(Item {
+            name: "Potion".to_string(),
+        })
         .with(crate::Viewable {
             name: "Potion".to_string(),
             description: vec![],
@@ -347,10 +349,8 @@ pub fn build_earth_scroll(ecs: &mut World, point: Point) -> Entity {
             bg: crate::bg_color(),
             zindex: 0,
         })
-        .with(Item)
-        .with(crate::EarthScroll {
-            radius: 4,
-            active_prob: 0.3,
+        .with(Item {
+            name: "Scroll of Earth".to_string(),
         })
         .with(crate::Viewable {
             name: "Scroll of Earth".to_string(),
@@ -0,0 +1,147 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+// the most times the same effect can be piled onto someone
+const MAX_STACKS: u32 = 3;
+// ticks between each point of burn damage
+const BURN_INTERVAL: u32 = 10;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum StatusKind {
+    // damage over time
+    Burn,
+    // no turns at all until it wears off
+    Freeze,
+    // damage every time the bleeder acts
+    Bleed,
+    // turns come at half speed
+    Slow,
+    // turns come at double speed
+    Haste,
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Burn => "burning",
+            StatusKind::Freeze => "frozen",
+            StatusKind::Bleed => "bleeding",
+            StatusKind::Slow => "slowed",
+            StatusKind::Haste => "hasted",
+        }
+    }
+
+    pub fn glyph(&self) -> rltk::FontCharType {
+        match self {
+            StatusKind::Burn => rltk::to_cp437('^'),
+            StatusKind::Freeze => rltk::to_cp437('*'),
+            StatusKind::Bleed => rltk::to_cp437(','),
+            StatusKind::Slow => rltk::to_cp437('~'),
+            StatusKind::Haste => rltk::to_cp437('>'),
+        }
+    }
+
+    pub fn color(&self) -> RGB {
+        match self {
+            StatusKind::Burn => burn_color(),
+            StatusKind::Freeze => freeze_color(),
+            StatusKind::Bleed => bleed_color(),
+            StatusKind::Slow => slow_color(),
+            StatusKind::Haste => haste_color(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub duration: u32,
+    pub stacks: u32,
+}
+
+impl StatusEffects {
+    // applying an effect again adds a stack and keeps whichever duration is longer
+    pub fn add(&mut self, kind: StatusKind, duration: u32) {
+        match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => {
+                effect.stacks = std::cmp::min(effect.stacks + 1, MAX_STACKS);
+                effect.duration = std::cmp::max(effect.duration, duration);
+            }
+            None => self.effects.push(StatusEffect {
+                kind,
+                duration,
+                stacks: 1,
+            }),
+        }
+    }
+
+    pub fn get(&self, kind: StatusKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|effect| effect.kind == kind)
+    }
+
+    // how fast the schedule counts down this tick. Slow skips every other tick, and cancels out
+    // with haste
+    pub fn get_delta(&self) -> i32 {
+        if self.get(StatusKind::Freeze).is_some() {
+            return 0;
+        }
+
+        match (self.get(StatusKind::Slow), self.get(StatusKind::Haste)) {
+            (Some(slow), None) => (slow.duration % 2) as i32,
+            (None, Some(_)) => 2,
+            _ => 1,
+        }
+    }
+
+    // counts every effect down by a tick and returns the burn damage dealt on this one
+    pub fn tick(&mut self) -> i32 {
+        let mut damage = 0;
+        for effect in self.effects.iter_mut() {
+            effect.duration = effect.duration.saturating_sub(1);
+            if effect.kind == StatusKind::Burn && effect.duration % BURN_INTERVAL == 0 {
+                damage += effect.stacks as i32;
+            }
+        }
+
+        self.effects.retain(|effect| effect.duration > 0);
+        damage
+    }
+
+    pub fn get_bleed_damage(&self) -> i32 {
+        self.get(StatusKind::Bleed)
+            .map_or(0, |effect| effect.stacks as i32)
+    }
+}
+
+pub fn apply_status(
+    statuses: &mut WriteStorage<StatusEffects>,
+    ent: Entity,
+    kind: StatusKind,
+    duration: u32,
+) {
+    match statuses.get_mut(ent) {
+        Some(effects) => effects.add(kind, duration),
+        None => {
+            let mut effects = StatusEffects {
+                effects: Vec::new(),
+            };
+            effects.add(kind, duration);
+            statuses
+                .insert(ent, effects)
+                .expect("Failed to insert StatusEffects");
+        }
+    }
+}
+
+// one colored glyph per effect, in the order they were applied
+pub fn draw_status_icons(ctx: &mut Rltk, x: i32, y: i32, effects: &StatusEffects) {
+    for (i, effect) in effects.effects.iter().enumerate() {
+        ctx.set(
+            x + i as i32,
+            y,
+            effect.kind.color(),
+            bg_color(),
+            effect.kind.glyph(),
+        );
+    }
+}
@@ -0,0 +1,44 @@
+// Persistent text files. Natively these are files in the working directory, and in the WASM
+// build they are kept in the browser's local storage under the same name
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(name: &str, text: &str) -> rltk::BError {
+    std::fs::write(name, text)?;
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(name).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn delete(name: &str) {
+    std::fs::remove_file(name).ok();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(name: &str, text: &str) -> rltk::BError {
+    local_storage()
+        .ok_or("Local storage is unavailable")?
+        .set_item(name, text)
+        .map_err(|_| "Failed to write to local storage")?;
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(name: &str) -> Option<String> {
+    local_storage()?.get_item(name).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn delete(name: &str) {
+    if let Some(storage) = local_storage() {
+        storage.remove_item(name).ok();
+    }
+}
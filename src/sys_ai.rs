@@ -1,14 +1,18 @@
-use crate::{Map, MoveIntent};
+use crate::flow_map::{FlowGoal, FlowMaps};
+use crate::MoveIntent;
 use rltk::Algorithm2D;
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Behavior {
     Sleep,
     Wander,
     Chase { target_point: rltk::Point },
     Attack { info: AttackInfo },
     AttackRecovery,
-    Flee,
+    Flee { turns: u32 },
 }
 
 #[derive(Clone)]
@@ -26,12 +30,77 @@ pub enum NextIntent {
     },
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct AttackInfo {
     attack_type: crate::AttackType,
     attack_loc: rltk::Point,
 }
 
+// A sound loud enough to wake sleeping monsters nearby. Alerts also call over anyone wandering
+#[derive(Copy, Clone)]
+pub struct Noise {
+    pub position: rltk::Point,
+    pub radius: i32,
+    pub alert: bool,
+}
+
+#[derive(Default)]
+pub struct Noises {
+    noises: Vec<Noise>,
+}
+
+impl Noises {
+    pub fn new() -> Noises {
+        Noises { noises: Vec::new() }
+    }
+
+    pub fn make(&mut self, position: rltk::Point, radius: i32) {
+        self.noises.push(Noise {
+            position,
+            radius,
+            alert: false,
+        });
+    }
+
+    pub fn alert(&mut self, position: rltk::Point, radius: i32) {
+        self.noises.push(Noise {
+            position,
+            radius,
+            alert: true,
+        });
+    }
+}
+
+// what a monster that can see the player decides to do with its turn
+enum Plan {
+    Attack(AttackInfo),
+    Step(rltk::Point),
+    Approach,
+}
+
+// how much a monster wants each kind of plan, before weighing in the attacks themselves
+const APPROACH_SCORE: f32 = 0.5;
+const RANGE_APPROACH_SCORE: f32 = 0.6;
+const DODGE_SCORE: f32 = 3.0;
+const KITE_SCORE: f32 = 1.0;
+// attacks that would land after the player's hit are rarely worth it, while catching the player
+// in recovery is
+const RISKY_MULTIPLIER: f32 = 0.2;
+const PUNISH_MULTIPLIER: f32 = 2.0;
+// stuns, knockbacks and the like count for this much damage
+const CONTROL_VALUE: f32 = 0.5;
+
+// what the rest of a squad asks of one of its members this turn
+#[derive(Copy, Clone, Default)]
+struct SquadOrders {
+    // where a squadmate last saw the player
+    sighting: Option<rltk::Point>,
+    // the side of the player a melee member should come in from
+    slot: Option<rltk::Point>,
+    // a ranged member that has got ahead of the squad's melee
+    exposed: bool,
+}
+
 struct AiStepData<'a> {
     ent: Entity,
     pos: &'a crate::Position,
@@ -39,8 +108,15 @@ struct AiStepData<'a> {
     viewshed: &'a crate::Viewshed,
     moveset: &'a crate::Moveset,
     multi: Option<&'a crate::MultiTile>,
+    health: Option<&'a crate::Health>,
+    allies: &'a [rltk::Point],
     player_point: rltk::Point,
+    player_frame: Option<crate::FrameData>,
+    danger: &'a [rltk::Point],
+    orders: SquadOrders,
+    flows: &'a mut FlowMaps,
     map: &'a mut crate::Map,
+    noises: &'a mut Noises,
     p_builder: &'a mut crate::ParticleBuilder,
     rng: &'a mut rltk::RandomNumberGenerator,
 }
@@ -62,8 +138,13 @@ impl<'a> System<'a> for AiSystem {
         ReadStorage<'a, crate::MultiTile>,
         ReadExpect<'a, Entity>,
         WriteExpect<'a, crate::Map>,
+        WriteStorage<'a, crate::BlockAttack>,
+        ReadStorage<'a, crate::CanBlock>,
         WriteExpect<'a, crate::ParticleBuilder>,
         WriteExpect<'a, rltk::RandomNumberGenerator>,
+        ReadStorage<'a, crate::Health>,
+        WriteExpect<'a, Noises>,
+        ReadStorage<'a, crate::Squad>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -81,13 +162,62 @@ impl<'a> System<'a> for AiSystem {
             multis,
             player,
             mut map,
+            mut blocks,
+            can_block,
             mut p_builder,
             mut rng,
+            healths,
+            mut noises,
+            squads,
         ) = data;
         let mut turn_done = Vec::new();
         let player_point = positions.get(*player).unwrap().as_point();
 
-        for (ent, _turn, pos, state, viewshed, moveset, multi) in (
+        // anyone close enough to last turn's noises goes to see what happened
+        let heard = std::mem::take(&mut noises.noises);
+        for (pos, state) in (&positions, &mut states).join() {
+            for noise in heard.iter() {
+                let wakes = match state.status {
+                    Behavior::Sleep => true,
+                    Behavior::Wander => noise.alert,
+                    _ => false,
+                };
+                let distance =
+                    rltk::DistanceAlg::Pythagoras.distance2d(pos.as_point(), noise.position);
+
+                if wakes && distance <= noise.radius as f32 {
+                    state.status = Behavior::Chase {
+                        target_point: noise.position,
+                    };
+                }
+            }
+        }
+
+        // the player's telegraphed attack, so monsters can weigh up whether to trade blows
+        let player_frame = frames.get(*player).copied();
+        let player_danger = attacks
+            .get(*player)
+            .map(crate::attack_type::each_intent_target)
+            .unwrap_or_default();
+
+        let monster_points = (&entities, &positions, &states)
+            .join()
+            .map(|(ent, pos, _)| (ent, pos.as_point()))
+            .collect::<Vec<_>>();
+
+        let squad_orders = Self::get_squad_orders(
+            &entities,
+            &positions,
+            &states,
+            &viewsheds,
+            &movesets,
+            &squads,
+            player_point,
+        );
+
+        let mut flows = FlowMaps::default();
+
+        for (ent, turn, pos, state, viewshed, moveset, multi) in (
             &entities,
             &can_act,
             &positions,
@@ -98,6 +228,52 @@ impl<'a> System<'a> for AiSystem {
         )
             .join()
         {
+            // a reaction is spent guarding against the attack, or getting out of its way
+            if turn.is_reaction {
+                let danger = turn
+                    .reaction_target
+                    .and_then(|attacker| attacks.get(attacker))
+                    .map(crate::attack_type::each_intent_target)
+                    .unwrap_or_default();
+
+                if let Some(can_block) = can_block.get(ent) {
+                    // now and then the guard goes up just as the blow lands, and parries it
+                    let parry_window = if rng.rand::<f32>() < crate::consts::MONSTER_PARRY_CHANCE {
+                        turn.reaction_target
+                            .and_then(|attacker| frames.get(attacker))
+                            .map_or(0, |frame| (frame.startup + 1).saturating_sub(frame.current))
+                    } else {
+                        0
+                    };
+
+                    blocks
+                        .insert(
+                            ent,
+                            crate::BlockAttack {
+                                block_amount: can_block.block_amount,
+                                parry_window,
+                            },
+                        )
+                        .expect("Failed to insert BlockAttack");
+                } else if let Some(intent) = Self::move_out_of(&danger, ent, pos, multi, &mut map) {
+                    moves
+                        .insert(ent, intent)
+                        .expect("Failed to insert movement from AI");
+                }
+
+                turn_done.push(ent);
+                continue;
+            }
+
+            // the guard only lasts until the next turn
+            blocks.remove(ent);
+
+            let allies = monster_points
+                .iter()
+                .filter(|(other, _)| *other != ent)
+                .map(|(_, point)| *point)
+                .collect::<Vec<_>>();
+
             let action = self.next_step(AiStepData {
                 ent,
                 pos,
@@ -105,8 +281,15 @@ impl<'a> System<'a> for AiSystem {
                 viewshed,
                 moveset,
                 multi,
+                health: healths.get(ent),
+                allies: &allies,
                 player_point,
+                player_frame,
+                danger: &player_danger,
+                orders: squad_orders.get(&ent).copied().unwrap_or_default(),
+                flows: &mut flows,
                 map: &mut *map,
+                noises: &mut noises,
                 p_builder: &mut *p_builder,
                 rng: &mut *rng,
             });
@@ -142,68 +325,78 @@ impl<'a> System<'a> for AiSystem {
 }
 
 impl AiSystem {
-    fn next_step(&mut self, data: AiStepData) -> NextIntent {
+    fn next_step(&mut self, mut data: AiStepData) -> NextIntent {
         loop {
             match data.state.status {
                 Behavior::Sleep => {
-                    // the do nothing state
-                    // TODO: trigger wake up
-                    return NextIntent::None;
+                    // the player can sneak by, as long as they keep their distance
+                    let distance = rltk::DistanceAlg::Pythagoras
+                        .distance2d(data.pos.as_point(), data.player_point);
+                    if distance > crate::consts::WAKE_DISTANCE
+                        || !Self::can_see_target(data.viewshed, data.player_point)
+                    {
+                        return NextIntent::None;
+                    }
+
+                    data.state.status = Behavior::Chase {
+                        target_point: data.player_point,
+                    };
+                    data.noises
+                        .alert(data.player_point, crate::consts::ALERT_RADIUS);
                 }
                 Behavior::Wander => {
                     if Self::can_see_target(data.viewshed, data.player_point) {
                         data.state.status = Behavior::Chase {
                             target_point: data.player_point,
                         };
+                        data.noises
+                            .alert(data.player_point, crate::consts::ALERT_RADIUS);
+                    } else if let Some(sighting) = data.orders.sighting {
+                        data.state.status = Behavior::Chase {
+                            target_point: sighting,
+                        };
                     } else {
                         return Self::move_random(data);
                     }
                 }
                 Behavior::Chase { target_point } => {
                     if Self::can_see_target(data.viewshed, data.player_point) {
+                        if !data.state.has_fled && Self::should_flee(&data) {
+                            data.state.has_fled = true;
+                            data.state.status = Behavior::Flee {
+                                turns: crate::consts::FLEE_TURNS,
+                            };
+                            continue;
+                        }
+
                         // track the player's current position
                         data.state.status = Behavior::Chase {
                             target_point: data.player_point,
                         };
 
-                        // check if we have any attacks that can hit
-                        let orig_point = data.pos.as_point();
-
-                        let rolled_prob: f32 = data.rng.rand();
-                        let mut cumul_prob: f32 = 0.0;
-                        let mut attack_found = false;
-
-                        // TODO: smarter attack selection
-                        // this is fine when all of the attacks have similar attack ranges
-                        // however, we might run into cases where we are in range to attack, but we decided to use an attack thats not valid
-                        for (potential_attack, chance) in data.moveset.moves.iter() {
-                            cumul_prob += chance;
-                            if rolled_prob > cumul_prob {
-                                continue;
+                        match Self::choose_plan(&mut data) {
+                            Plan::Attack(info) => {
+                                data.state.status = Behavior::Attack { info };
                             }
-
-                            if let Some(attack_loc) = crate::attack_type::is_attack_valid(
-                                *potential_attack,
-                                orig_point,
-                                data.player_point,
-                            ) {
-                                data.state.status = Behavior::Attack {
-                                    info: AttackInfo {
-                                        attack_type: *potential_attack,
-                                        attack_loc,
+                            Plan::Step(loc) => {
+                                return NextIntent::Move {
+                                    intent: MoveIntent {
+                                        loc,
+                                        force_facing: None,
+                                        delay: 0,
                                     },
                                 };
-                                attack_found = true;
-                                break;
                             }
-                        }
-
-                        if !attack_found {
-                            // if we can't hit, just move towards the player
-                            return Self::move_towards(data.player_point, data);
+                            Plan::Approach => {
+                                let target_point = Self::get_approach_point(&mut data);
+                                return Self::move_towards(target_point, data);
+                            }
                         }
                     } else {
-                        // we don't see the player, move to the last tracked point
+                        // we don't see the player, move to the last tracked point, or wherever the
+                        // squad has seen them since
+                        let target_point = data.orders.sighting.unwrap_or(target_point);
+                        data.state.status = Behavior::Chase { target_point };
                         return Self::move_towards(target_point, data);
                     }
                 }
@@ -228,12 +421,360 @@ impl AiSystem {
                         data.state.status = Behavior::Wander;
                     }
                 }
-                Behavior::Flee => {
-                    // TODO
-                    return NextIntent::None;
+                Behavior::Flee { turns } => {
+                    let sees_player = Self::can_see_target(data.viewshed, data.player_point);
+                    let ally = Self::nearest_ally(&data);
+
+                    // safe once out of sight with a friend close by
+                    let regrouped = !sees_player
+                        && ally.is_some_and(|ally| {
+                            rltk::DistanceAlg::Pythagoras.distance2d(data.pos.as_point(), ally)
+                                <= crate::consts::REGROUP_DISTANCE
+                        });
+
+                    if turns == 0 || regrouped {
+                        data.state.status = if sees_player {
+                            Behavior::Chase {
+                                target_point: data.player_point,
+                            }
+                        } else {
+                            Behavior::Wander
+                        };
+                        continue;
+                    }
+
+                    match Self::move_away(data.player_point, ally, &mut data) {
+                        Some(loc) => {
+                            data.state.status = Behavior::Flee { turns: turns - 1 };
+                            return NextIntent::Move {
+                                intent: MoveIntent {
+                                    loc,
+                                    force_facing: None,
+                                    delay: 0,
+                                },
+                            };
+                        }
+                        None => {
+                            // cornered, so turn and fight
+                            data.state.status = Behavior::Chase {
+                                target_point: data.player_point,
+                            };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Scores everything worth doing this turn: each attack that can land, weighed by what it does
+    // and how it trades against the player's current attack, plus getting out of the way, backing
+    // off to shooting range, or closing in
+    fn choose_plan(data: &mut AiStepData) -> Plan {
+        let curr_point = data.pos.as_point();
+        let distance = rltk::DistanceAlg::Chebyshev.distance2d(curr_point, data.player_point);
+        let in_danger = data.danger.contains(&curr_point);
+
+        let mut best = (Plan::Approach, APPROACH_SCORE);
+        let mut consider = |plan: Plan, score: f32| {
+            if score > best.1 {
+                best = (plan, score);
+            }
+        };
+
+        for (attack_type, weight) in data.moveset.moves.iter() {
+            let attack_loc = match crate::attack_type::is_attack_valid(
+                *attack_type,
+                curr_point,
+                data.player_point,
+            ) {
+                None => continue,
+                Some(attack_loc) => attack_loc,
+            };
+
+            let startup = crate::attack_type::get_startup(*attack_type);
+            let mut score = weight * (1.0 + Self::get_power(*attack_type, true));
+
+            if let Some(frame) = data.player_frame {
+                let hit_at = frame.startup + frame.active;
+                let recovery_end = hit_at + frame.recovery;
+
+                if in_danger
+                    && frame.current <= frame.startup
+                    && frame.startup.saturating_sub(frame.current) <= startup
+                {
+                    score *= RISKY_MULTIPLIER;
+                } else if frame.current >= hit_at
+                    && recovery_end.saturating_sub(frame.current) >= startup
+                {
+                    score *= PUNISH_MULTIPLIER;
+                }
+            }
+
+            // keep some variety in what gets picked
+            score *= 0.75 + data.rng.rand::<f32>() * 0.5;
+            consider(
+                Plan::Attack(AttackInfo {
+                    attack_type: *attack_type,
+                    attack_loc,
+                }),
+                score,
+            );
+        }
+
+        if in_danger {
+            if let Some(intent) =
+                Self::move_out_of(data.danger, data.ent, data.pos, data.multi, data.map)
+            {
+                consider(Plan::Step(intent.loc), DODGE_SCORE);
+            }
+        }
+
+        // ranged monsters make for their shooting range, and back off to it along the flow when
+        // the player gets too close. They hang further back when the squad's melee hasn't caught
+        // up yet
+        if let Some(shooting_range) = Self::get_shooting_range(data.moveset) {
+            let shooting_range = shooting_range + data.orders.exposed as i32;
+            let goal = FlowGoal::Range {
+                center: data.map.point2d_to_index(data.player_point),
+                range: shooting_range,
+            };
+
+            if let Some(loc) = data
+                .flows
+                .next_step(data.map, data.ent, curr_point, goal, data.multi)
+            {
+                if (distance as i32) < shooting_range {
+                    consider(
+                        Plan::Step(loc),
+                        KITE_SCORE + (shooting_range - distance as i32) as f32,
+                    );
+                } else {
+                    consider(Plan::Step(loc), RANGE_APPROACH_SCORE);
+                }
+            }
+        }
+
+        best.0
+    }
+
+    // ranged attacks that hurt are worth keeping some distance for
+    fn get_shooting_range(moveset: &crate::Moveset) -> Option<i32> {
+        moveset
+            .moves
+            .iter()
+            .filter(|(attack_type, _)| Self::get_power(*attack_type, false) > 0.0)
+            .map(|(attack_type, _)| Self::get_reach(*attack_type))
+            .filter(|reach| *reach > 2)
+            .max()
+            .map(|reach| reach / 2 + 1)
+    }
+
+    // melee members come in from their own side of the player, as long as that side is open
+    fn get_approach_point(data: &mut AiStepData) -> rltk::Point {
+        let distance =
+            rltk::DistanceAlg::Chebyshev.distance2d(data.pos.as_point(), data.player_point);
+
+        match data.orders.slot {
+            Some(slot)
+                if distance > 1.0
+                    && data
+                        .map
+                        .is_exit_valid_for(slot.x, slot.y, data.ent, data.multi) =>
+            {
+                slot
+            }
+            _ => data.player_point,
+        }
+    }
+
+    // Squads share what any awake member can see. Melee members split up around the player,
+    // with the closest taking the near side and the rest flanking, while ranged members try to
+    // stay behind them
+    fn get_squad_orders(
+        entities: &Entities,
+        positions: &ReadStorage<crate::Position>,
+        states: &WriteStorage<crate::AiState>,
+        viewsheds: &ReadStorage<crate::Viewshed>,
+        movesets: &ReadStorage<crate::Moveset>,
+        squads: &ReadStorage<crate::Squad>,
+        player_point: rltk::Point,
+    ) -> HashMap<Entity, SquadOrders> {
+        let mut members: BTreeMap<i32, Vec<(Entity, rltk::Point, bool)>> = BTreeMap::new();
+        let mut sightings: HashSet<i32> = HashSet::new();
+
+        for (ent, pos, state, viewshed, moveset, squad) in
+            (entities, positions, states, viewsheds, movesets, squads).join()
+        {
+            if matches!(state.status, Behavior::Sleep | Behavior::Flee { .. }) {
+                continue;
+            }
+
+            if Self::can_see_target(viewshed, player_point) {
+                sightings.insert(squad.id);
+            }
+
+            let is_ranged = Self::get_shooting_range(moveset).is_some();
+            members
+                .entry(squad.id)
+                .or_default()
+                .push((ent, pos.as_point(), is_ranged));
+        }
+
+        let distance_to_player =
+            |point: rltk::Point| rltk::DistanceAlg::Chebyshev.distance2d(point, player_point);
+        let mut orders = HashMap::new();
+
+        for (squad, mut squad_members) in members {
+            let sighting = sightings.contains(&squad).then_some(player_point);
+            squad_members.sort_by(|(_, point1, _), (_, point2, _)| {
+                distance_to_player(*point1).total_cmp(&distance_to_player(*point2))
+            });
+
+            let closest_melee = squad_members
+                .iter()
+                .find(|(_, _, is_ranged)| !is_ranged)
+                .map(|(_, point, _)| *point);
+            let front = closest_melee
+                .and_then(|point| crate::Direction::get_direction_towards(player_point, point))
+                .unwrap_or(crate::Direction::N);
+            let sides = [front, front.opp(), front.left(), front.right()];
+
+            let mut melee_count = 0;
+            for (ent, point, is_ranged) in squad_members {
+                let mut member_orders = SquadOrders {
+                    sighting,
+                    ..Default::default()
+                };
+
+                if is_ranged {
+                    member_orders.exposed = closest_melee.is_some_and(|melee_point| {
+                        distance_to_player(point) <= distance_to_player(melee_point)
+                    });
+                } else {
+                    let side = sides[melee_count % sides.len()];
+                    member_orders.slot =
+                        Some(crate::Direction::point_in_direction(player_point, side));
+                    melee_count += 1;
                 }
+
+                orders.insert(ent, member_orders);
             }
         }
+
+        orders
+    }
+
+    // how far out an attack can be aimed
+    fn get_reach(attack_type: crate::AttackType) -> i32 {
+        let range = crate::attack_type::get_attack_range(attack_type);
+        crate::range_type::resolve_range_at(&range, rltk::Point::zero())
+            .iter()
+            .map(|point| std::cmp::max(point.x.abs(), point.y.abs()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // damage dealt, plus a bit for anything that disrupts the target. Projectiles are as strong
+    // as what they hit with
+    fn get_power(attack_type: crate::AttackType, with_control: bool) -> f32 {
+        let control = if with_control { CONTROL_VALUE } else { 0.0 };
+
+        crate::attack_type::get_attack_traits(attack_type)
+            .iter()
+            .map(|att_trait| match att_trait {
+                crate::AttackTrait::Damage { amount } => *amount as f32,
+                crate::AttackTrait::FollowsPath { on_hit, .. } => {
+                    Self::get_power(*on_hit, with_control)
+                }
+                crate::AttackTrait::Knockback { .. }
+                | crate::AttackTrait::Pull { .. }
+                | crate::AttackTrait::Stun { .. }
+                | crate::AttackTrait::Status { .. } => control,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    // run when badly hurt, or when half beaten with nobody in sight to help. Large bodies stand
+    // their ground
+    fn should_flee(data: &AiStepData) -> bool {
+        let health = match data.health {
+            None => return false,
+            Some(health) => health,
+        };
+        if data.multi.is_some() {
+            return false;
+        }
+
+        let alone = !data
+            .allies
+            .iter()
+            .any(|ally| Self::can_see_target(data.viewshed, *ally));
+
+        health.current * 4 <= health.max || (alone && health.current * 2 <= health.max)
+    }
+
+    fn nearest_ally(data: &AiStepData) -> Option<rltk::Point> {
+        let curr_point = data.pos.as_point();
+        data.allies
+            .iter()
+            .filter(|ally| Self::can_see_target(data.viewshed, **ally))
+            .min_by_key(|ally| rltk::DistanceAlg::Manhattan.distance2d(curr_point, **ally) as i32)
+            .copied()
+    }
+
+    // step to whichever safe tile puts the most room between us and the threat, drifting
+    // towards a friend if there is one
+    fn move_away(
+        threat: rltk::Point,
+        ally: Option<rltk::Point>,
+        data: &mut AiStepData,
+    ) -> Option<rltk::Point> {
+        let score = |point: rltk::Point| {
+            let ally_distance = ally.map_or(0.0, |ally| {
+                rltk::DistanceAlg::Pythagoras.distance2d(point, ally)
+            });
+            rltk::DistanceAlg::Pythagoras.distance2d(point, threat) - ally_distance * 0.5
+        };
+
+        let curr_point = data.pos.as_point();
+        let curr_score = score(curr_point);
+        let curr_index = data.map.point2d_to_index(curr_point);
+        let exits = data
+            .map
+            .get_available_exits_for(curr_index, data.ent, data.multi);
+
+        exits
+            .iter()
+            .map(|(exit, _)| data.map.index_to_point2d(*exit))
+            .filter(|point| !data.map.is_tile_hazard(point.x, point.y))
+            .map(|point| (point, score(point)))
+            .filter(|(_, point_score)| *point_score > curr_score)
+            .max_by(|(_, score1), (_, score2)| score1.total_cmp(score2))
+            .map(|(point, _)| point)
+    }
+
+    fn move_out_of(
+        danger: &[rltk::Point],
+        ent: Entity,
+        pos: &crate::Position,
+        multi: Option<&crate::MultiTile>,
+        map: &mut crate::Map,
+    ) -> Option<MoveIntent> {
+        if !danger.contains(&pos.as_point()) {
+            return None;
+        }
+
+        let curr_index = map.get_index(pos.x, pos.y);
+        map.get_available_exits_for(curr_index, ent, multi)
+            .iter()
+            .map(|(exit, _)| map.index_to_point2d(*exit))
+            .find(|point| !danger.contains(point))
+            .map(|loc| MoveIntent {
+                loc,
+                force_facing: None,
+                delay: 0,
+            })
     }
 
     fn move_random(data: AiStepData) -> NextIntent {
@@ -260,64 +801,45 @@ impl AiSystem {
     }
 
     fn move_towards(target_point: rltk::Point, data: AiStepData) -> NextIntent {
-        let curr_index = data.map.get_index(data.pos.x, data.pos.y);
-        let target_index = data.map.point2d_to_index(target_point);
-        let path = Self::get_path_to(data.ent, data.map, curr_index, target_index, data.multi);
-
-        match path {
-            None => {
-                // TODO: this state lags the game, presumably because we recompute a bunch of expensive a* paths
-                if let Some(path) = &data.state.prev_path {
-                    if data.state.path_step < path.steps.len() {
-                        let next_pos = data.map.index_to_point2d(path.steps[data.state.path_step]);
-                        data.state.path_step += 1;
-
-                        return NextIntent::Move {
-                            intent: MoveIntent {
-                                loc: next_pos,
-                                force_facing: None,
-                                delay: 0,
-                            },
-                        };
-                    }
-                }
-
-                // no path to target, attempt to move towards the target
-                let curr_point = data.pos.as_point();
-                let dir = crate::Direction::get_direction_towards(curr_point, target_point)
-                    .unwrap_or(crate::Direction::N);
-                let next_point = crate::Direction::point_in_direction(curr_point, dir);
-
-                if data
-                    .map
-                    .is_exit_valid_for(next_point.x, next_point.y, data.ent, data.multi)
-                {
-                    return NextIntent::Move {
-                        intent: MoveIntent {
-                            loc: next_point,
-                            force_facing: None,
-                            delay: 0,
-                        },
-                    };
-                }
+        let goal = FlowGoal::Point(data.map.point2d_to_index(target_point));
+        Self::follow_flow(goal, target_point, data)
+    }
 
-                // can't move towards the target, just make a random move
-                return Self::move_random(data);
-            }
-            Some(path) => {
-                let next_pos = data.map.index_to_point2d(path.steps[1]);
-                data.state.prev_path = Some(path);
-                data.state.path_step = 2;
+    // step down a shared flow map, falling back to heading straight for the target when every
+    // way closer is taken
+    fn follow_flow(goal: FlowGoal, target_point: rltk::Point, data: AiStepData) -> NextIntent {
+        let curr_point = data.pos.as_point();
+        if let Some(loc) = data
+            .flows
+            .next_step(data.map, data.ent, curr_point, goal, data.multi)
+        {
+            return NextIntent::Move {
+                intent: MoveIntent {
+                    loc,
+                    force_facing: None,
+                    delay: 0,
+                },
+            };
+        }
 
+        if let Some(dir) = crate::Direction::get_direction_towards(curr_point, target_point) {
+            let next_point = crate::Direction::point_in_direction(curr_point, dir);
+            if data
+                .map
+                .is_exit_valid_for(next_point.x, next_point.y, data.ent, data.multi)
+            {
                 return NextIntent::Move {
                     intent: MoveIntent {
-                        loc: next_pos,
+                        loc: next_point,
                         force_facing: None,
                         delay: 0,
                     },
                 };
             }
         }
+
+        // can't move towards the target, just make a random move
+        Self::move_random(data)
     }
 
     fn can_see_target(viewshed: &crate::Viewshed, target: rltk::Point) -> bool {
@@ -326,22 +848,4 @@ impl AiSystem {
             .iter()
             .any(|pos| pos.x == target.x && pos.y == target.y)
     }
-
-    fn get_path_to(
-        entity: Entity,
-        map: &mut Map,
-        curr_index: usize,
-        target_index: usize,
-        multi_component: Option<&crate::MultiTile>,
-    ) -> Option<rltk::NavigationPath> {
-        map.set_additional_args(entity, multi_component);
-        let path = rltk::a_star_search(curr_index, target_index, &*map);
-
-        if path.success && path.steps.len() > 1 {
-            return Some(path);
-        } else {
-            println!("No path exists!");
-            return None;
-        }
-    }
 }
@@ -1,14 +1,14 @@
 use crate::attack_type;
 use rltk::Algorithm2D;
 use specs::prelude::*;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct AttackSystem;
 
 impl<'a> System<'a> for AttackSystem {
     type SystemData = (
         Entities<'a>,
-        ReadExpect<'a, crate::Map>,
+        WriteExpect<'a, crate::Map>,
         ReadStorage<'a, crate::Position>,
         WriteStorage<'a, crate::AttackIntent>,
         WriteStorage<'a, crate::AttackInProgress>,
@@ -27,12 +27,18 @@ impl<'a> System<'a> for AttackSystem {
         WriteStorage<'a, crate::Fragile>,
         ReadStorage<'a, crate::Viewable>,
         WriteExpect<'a, crate::GameLog>,
+        ReadStorage<'a, crate::Armor>,
+        WriteExpect<'a, crate::MissionInfo>,
+        ReadStorage<'a, crate::BlockAttack>,
+        WriteStorage<'a, crate::StatusEffects>,
+        WriteStorage<'a, crate::PushForce>,
+        WriteExpect<'a, crate::Noises>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            map,
+            mut map,
             positions,
             mut attacks,
             mut attacks_in_progress,
@@ -51,6 +57,12 @@ impl<'a> System<'a> for AttackSystem {
             mut breakables,
             viewables,
             mut log,
+            armors,
+            mut mission,
+            blocks,
+            mut statuses,
+            mut pushes,
+            mut noises,
         ) = data;
         let mut finished_attacks = Vec::new();
 
@@ -74,6 +86,11 @@ impl<'a> System<'a> for AttackSystem {
                 finished_attacks.push(ent);
             }
 
+            // the fight can be heard from a way off
+            if frame.current == frame.startup + 1 {
+                noises.make(intent.loc, crate::consts::COMBAT_NOISE_RADIUS);
+            }
+
             for att_trait in trait_list {
                 match att_trait {
                     crate::AttackTrait::Knockback { amount } => {
@@ -86,95 +103,13 @@ impl<'a> System<'a> for AttackSystem {
                                 .unwrap()
                                 .to_point();
 
-                            // check for collision
-                            let mut next_x = ent_pos.x;
-                            let mut next_y = ent_pos.y;
-                            for _ in 0..amount {
-                                next_x += offset.x;
-                                next_y += offset.y;
-
-                                // if we collide into something, rewind the attempted movement
-                                // and insert a stun
-                                if !map.is_tile_valid(next_x, next_y) {
-                                    if let Some(collided_ent) =
-                                        map.creature_map.get(&map.get_index(next_x, next_y))
-                                    {
-                                        if let Some(fragile) = breakables.get_mut(*collided_ent) {
-                                            fragile.was_hit = true;
-                                            p_builder.make_hit_particle(*collided_ent);
-                                        }
-
-                                        stuns
-                                            .insert(
-                                                *collided_ent,
-                                                crate::Stunned {
-                                                    duration: crate::consts::WALL_HIT_STUN_DURATION,
-                                                },
-                                            )
-                                            .ok();
-                                    }
-
-                                    if let Some(view) = viewables.get(ent_hit) {
-                                        if map.visible_tiles[map.get_index(next_x, next_y)] {
-                                            if ent_hit == *player {
-                                                log.add("You are knocked into something");
-                                            } else {
-                                                log.add(format!(
-                                                    "A {} is knocked into something",
-                                                    view.name.to_lowercase()
-                                                ));
-                                            }
-                                        }
-                                    }
-
-                                    next_x -= offset.x;
-                                    next_y -= offset.y;
-
-                                    stuns
-                                        .insert(
-                                            ent_hit,
-                                            crate::Stunned {
-                                                duration: crate::consts::WALL_HIT_STUN_DURATION,
-                                            },
-                                        )
-                                        .ok();
-                                    break;
-                                }
-
-                                if map.is_tile_water(next_x, next_y) {
-                                    stuns
-                                        .insert(
-                                            ent_hit,
-                                            crate::Stunned {
-                                                duration: crate::consts::WALL_HIT_STUN_DURATION,
-                                            },
-                                        )
-                                        .ok();
-
-                                    if let Some(view) = viewables.get(ent_hit) {
-                                        if map.visible_tiles[map.get_index(next_x, next_y)] {
-                                            if ent_hit == *player {
-                                                log.add("You are knocked into the water");
-                                            } else {
-                                                log.add(format!(
-                                                    "A {} is knocked into the water",
-                                                    view.name.to_lowercase()
-                                                ));
-                                            }
-                                        }
-                                    }
-
-                                    break;
-                                }
-                            }
-
-                            movements
+                            // the push system bounces, chains and drops things into hazards
+                            pushes
                                 .insert(
                                     ent_hit,
-                                    crate::MoveIntent {
-                                        loc: rltk::Point::new(next_x, next_y),
-                                        force_facing: None,
-                                        delay: 0,
+                                    crate::PushForce {
+                                        delta: offset * amount,
+                                        impact_damage: crate::consts::PUSH_IMPACT_DAMAGE,
                                     },
                                 )
                                 .ok();
@@ -189,6 +124,19 @@ impl<'a> System<'a> for AttackSystem {
                             let offset = crate::Direction::get_direction_towards(ent_pos, src_pos)
                                 .unwrap()
                                 .to_point();
+
+                            if multis.get(ent_hit).is_some() {
+                                pushes
+                                    .insert(
+                                        ent_hit,
+                                        crate::PushForce {
+                                            delta: offset * amount,
+                                            impact_damage: crate::consts::PUSH_IMPACT_DAMAGE,
+                                        },
+                                    )
+                                    .ok();
+                                continue;
+                            }
                             let mut pull_path = rltk::line2d_bresenham(src_pos, ent_pos);
                             pull_path.pop();
 
@@ -252,6 +200,10 @@ impl<'a> System<'a> for AttackSystem {
                                         )
                                         .ok();
 
+                                    if ent_hit != *player {
+                                        mission.dunked += 1;
+                                    }
+
                                     if let Some(view) = viewables.get(ent_hit) {
                                         if map.visible_tiles[map.get_index(next_x, next_y)] {
                                             if ent_hit == *player {
@@ -293,13 +245,45 @@ impl<'a> System<'a> for AttackSystem {
                                 p_builder.make_hit_particle(ent_hit);
                             }
 
+                            let mut amount = match armors.get(ent_hit) {
+                                Some(armor) => std::cmp::max(amount - armor.amount, 1),
+                                None => amount,
+                            };
+
+                            // a guard holds as long as there is stamina to spend on it
+                            if let Some(block) = blocks.get(ent_hit) {
+                                let cost = crate::consts::BLOCK_STAMINA_COST;
+                                let can_block = match stams.get_mut(ent_hit) {
+                                    None => true,
+                                    Some(stamina) if stamina.current >= cost => {
+                                        stamina.current -= cost;
+                                        stamina.recover = false;
+                                        true
+                                    }
+                                    Some(_) => false,
+                                };
+
+                                if can_block {
+                                    amount = std::cmp::max(amount - block.block_amount as i32, 0);
+                                }
+                            }
+
                             if let Some(aff_health) = healths.get_mut(ent_hit) {
                                 aff_health.current -= amount;
 
                                 let pos = positions.get(ent_hit).unwrap();
                                 if let Some(view) = viewables.get(ent_hit) {
                                     if map.visible_tiles[map.get_index(pos.x, pos.y)] {
-                                        if ent_hit == *player {
+                                        if amount == 0 {
+                                            if ent_hit == *player {
+                                                log.add("You block the hit");
+                                            } else {
+                                                log.add(format!(
+                                                    "A {} blocks the hit",
+                                                    view.name.to_lowercase()
+                                                ));
+                                            }
+                                        } else if ent_hit == *player {
                                             log.add(format!("You are hit for {}", amount));
                                         } else {
                                             log.add(format!(
@@ -317,7 +301,7 @@ impl<'a> System<'a> for AttackSystem {
                                             let adj_part_pos = pos.as_point() + *part_pos;
 
                                             if hit_locs.contains(&adj_part_pos) {
-                                                part.health -= 1;
+                                                part.health -= amount;
                                                 break;
                                             }
                                         }
@@ -340,7 +324,7 @@ impl<'a> System<'a> for AttackSystem {
                         }
                     }
                     crate::AttackTrait::Movement { delay } => {
-                        let targets = attack_type::each_attack_target(intent.main, intent.loc);
+                        let targets = attack_type::each_intent_target(intent);
                         assert!(targets.len() == 1);
 
                         movements
@@ -361,12 +345,60 @@ impl<'a> System<'a> for AttackSystem {
                     }
                     crate::AttackTrait::NeedsStamina { amount } => {
                         if let Some(stamina) = stams.get_mut(ent) {
-                            stamina.current -= amount;
+                            stamina.current -= std::cmp::max(amount - intent.bonus.stamina, 0);
                             stamina.recover = false;
                         }
                     }
-                    crate::AttackTrait::Heal { amount: _ } => {
-                        //
+                    crate::AttackTrait::Status { kind, duration } => {
+                        if kind == crate::status::StatusKind::Burn {
+                            for point in attack_type::each_intent_target(intent) {
+                                if map.in_bounds(point) {
+                                    let index = map.point2d_to_index(point);
+                                    map.ignite(index);
+                                }
+                            }
+                        }
+
+                        let ents_hit = self.get_hit_entities(&mut p_builder, &map, ent, intent);
+                        for (ent_hit, _) in ents_hit {
+                            if invulns.get(ent_hit).is_some() {
+                                continue;
+                            }
+
+                            crate::status::apply_status(&mut statuses, ent_hit, kind, duration);
+
+                            let pos = positions.get(ent_hit).unwrap();
+                            if let Some(view) = viewables.get(ent_hit) {
+                                if map.visible_tiles[map.get_index(pos.x, pos.y)] {
+                                    if ent_hit == *player {
+                                        log.add(format!("You are {}", kind.name()));
+                                    } else {
+                                        log.add(format!(
+                                            "A {} is {}",
+                                            view.name.to_lowercase(),
+                                            kind.name()
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    crate::AttackTrait::SelfStatus { kind, duration } => {
+                        crate::status::apply_status(&mut statuses, ent, kind, duration);
+
+                        if ent == *player {
+                            log.add(format!("You are {}", kind.name()));
+                        }
+                    }
+                    crate::AttackTrait::Heal { amount } => {
+                        if let Some(health) = healths.get_mut(ent) {
+                            let amount = std::cmp::min(amount, health.max - health.current);
+                            health.current += amount;
+
+                            if ent == *player {
+                                log.add(format!("You heal for {}", amount));
+                            }
+                        }
                     }
                     crate::AttackTrait::LanceCharge { dir } => {
                         if ent == *player {
@@ -390,13 +422,14 @@ impl<'a> System<'a> for AttackSystem {
                                         step_delay,
                                         cur_delay: 0,
                                         on_hit,
+                                        bonus: intent.bonus,
                                     },
                                 )
                                 .ok();
                         }
                     }
                     crate::AttackTrait::CreatesWalls => {
-                        let targets = attack_type::each_attack_target(intent.main, intent.loc);
+                        let targets = attack_type::each_intent_target(intent);
                         for t in targets.iter() {
                             if !map.is_tile_occupied(t.x, t.y) {
                                 s_builder.spawn(crate::SpawnRequest {
@@ -424,9 +457,9 @@ impl AttackSystem {
         map: &crate::Map,
         ent: Entity,
         intent: &crate::AttackIntent,
-    ) -> HashMap<specs::Entity, Vec<rltk::Point>> {
-        let targets = attack_type::each_attack_target(intent.main, intent.loc);
-        let mut ents_hit = HashMap::new();
+    ) -> BTreeMap<specs::Entity, Vec<rltk::Point>> {
+        let targets = attack_type::each_intent_target(intent);
+        let mut ents_hit = BTreeMap::new();
 
         for point in targets {
             p_builder.make_bg_particle(point);
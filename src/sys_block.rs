@@ -0,0 +1,110 @@
+use crate::attack_type;
+use rltk::Algorithm2D;
+use specs::prelude::*;
+
+// Runs as attacks reach their active frame, before they land. A guard that was only just raised
+// parries the hit, cancelling the attack and giving the defender a free turn to counter with
+pub struct BlockSystem;
+
+impl<'a> System<'a> for BlockSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, crate::Map>,
+        WriteStorage<'a, crate::BlockAttack>,
+        WriteStorage<'a, crate::AttackIntent>,
+        WriteStorage<'a, crate::AttackInProgress>,
+        WriteStorage<'a, crate::FrameData>,
+        WriteStorage<'a, crate::CanActFlag>,
+        ReadStorage<'a, crate::Viewable>,
+        ReadStorage<'a, crate::Position>,
+        WriteExpect<'a, crate::ParticleBuilder>,
+        WriteExpect<'a, crate::GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            map,
+            mut blocks,
+            mut attacks,
+            mut attacks_in_progress,
+            mut frames,
+            mut can_act,
+            viewables,
+            positions,
+            mut p_builder,
+            mut log,
+        ) = data;
+        let mut parries = Vec::new();
+
+        // projectiles have no position, only blows struck in person can be parried
+        for (attacker, intent, frame, _) in (&entities, &attacks, &frames, &positions).join() {
+            if frame.cancelled || frame.current != frame.startup + 1 {
+                continue;
+            }
+
+            let deals_damage = attack_type::get_attack_traits(intent.main)
+                .iter()
+                .any(|tr| matches!(tr, attack_type::AttackTrait::Damage { .. }));
+            if !deals_damage {
+                continue;
+            }
+
+            let parrier = attack_type::each_intent_target(intent)
+                .iter()
+                .filter(|point| map.in_bounds(**point))
+                .filter_map(|point| map.creature_map.get(&map.point2d_to_index(*point)))
+                .find(|ent| {
+                    **ent != attacker
+                        && blocks
+                            .get(**ent)
+                            .is_some_and(|block| block.parry_window > 0)
+                });
+
+            if let Some(parrier) = parrier {
+                parries.push((*parrier, attacker));
+            }
+        }
+
+        for (parrier, attacker) in parries {
+            attacks.remove(attacker);
+            attacks_in_progress.remove(attacker);
+            if let Some(frame) = frames.get_mut(attacker) {
+                frame.cancelled = true;
+            }
+
+            can_act
+                .insert(
+                    parrier,
+                    crate::CanActFlag {
+                        is_reaction: false,
+                        reaction_target: Some(attacker),
+                    },
+                )
+                .expect("Failed to insert CanActFlag");
+            p_builder.make_stun_particle(attacker);
+
+            if parrier == *player {
+                if let Some(view) = viewables.get(attacker) {
+                    log.add(format!(
+                        "You parry the {}, counter!",
+                        view.name.to_lowercase()
+                    ));
+                }
+            } else if attacker == *player {
+                if let Some(view) = viewables.get(parrier) {
+                    log.add(format!(
+                        "A {} parries your attack",
+                        view.name.to_lowercase()
+                    ));
+                }
+            }
+        }
+
+        for block in (&mut blocks).join() {
+            block.parry_window = block.parry_window.saturating_sub(1);
+        }
+    }
+}
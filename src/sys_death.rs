@@ -15,6 +15,10 @@ impl<'a> System<'a> for DeathSystem {
         WriteStorage<'a, crate::Viewshed>,
         ReadStorage<'a, crate::Viewable>,
         WriteExpect<'a, crate::GameLog>,
+        ReadStorage<'a, crate::Bounty>,
+        WriteExpect<'a, crate::inventory::Earnings>,
+        ReadStorage<'a, crate::MissionTarget>,
+        WriteExpect<'a, crate::MissionInfo>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -30,6 +34,10 @@ impl<'a> System<'a> for DeathSystem {
             mut viewsheds,
             viewables,
             mut log,
+            bounties,
+            mut earnings,
+            targets,
+            mut mission,
         ) = data;
         let mut dead = Vec::new();
 
@@ -52,6 +60,15 @@ impl<'a> System<'a> for DeathSystem {
                     if map.visible_tiles[pos_index] {
                         log.add(format!("A {} is knocked out", view.name.to_lowercase()))
                     }
+
+                    if let Some(bounty) = bounties.get(ent) {
+                        earnings.money += bounty.money;
+                    }
+
+                    if targets.get(ent).is_some() {
+                        mission.remove(ent);
+                        log.add("The marked target is down");
+                    }
                 } else {
                     *run_state = crate::RunState::Dead { success: false };
                     log.add("You are knocked out! Press r to try again")
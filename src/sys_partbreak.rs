@@ -1,5 +1,7 @@
 use specs::prelude::*;
 
+// Parts that run out of health break off. The boss loses the attacks that went with the part,
+// drops whatever it was carrying, and speeds up
 pub struct PartBreakSystem;
 
 impl<'a> System<'a> for PartBreakSystem {
@@ -7,17 +9,68 @@ impl<'a> System<'a> for PartBreakSystem {
         Entities<'a>,
         ReadStorage<'a, crate::Position>,
         WriteStorage<'a, crate::MultiTile>,
+        WriteStorage<'a, crate::Moveset>,
+        ReadStorage<'a, crate::Boss>,
+        WriteStorage<'a, crate::Schedulable>,
+        ReadStorage<'a, crate::Viewable>,
+        WriteExpect<'a, crate::Spawner>,
+        WriteExpect<'a, crate::GameLog>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, positions, mut multitiles) = data;
+        let (
+            entities,
+            positions,
+            mut multitiles,
+            mut movesets,
+            bosses,
+            mut schedulables,
+            viewables,
+            mut s_builder,
+            mut log,
+        ) = data;
 
-        for (_, _, multis) in (&entities, &positions, &mut multitiles).join() {
+        for (ent, pos, multis) in (&entities, &positions, &mut multitiles).join() {
             for part in multis.part_list.iter_mut() {
-                if part.health <= 0 {
-                    for v in part.symbol_map.values_mut() {
-                        *v = rltk::to_cp437('x');
+                if part.health > 0 || part.broken {
+                    continue;
+                }
+
+                part.broken = true;
+                for v in part.symbol_map.values_mut() {
+                    *v = rltk::to_cp437('x');
+                }
+
+                if let Some(moveset) = movesets.get_mut(ent) {
+                    for part_move in &part.moves {
+                        if let Some(index) = moveset.moves.iter().position(|m| m == part_move) {
+                            moveset.moves.remove(index);
+                        }
+                    }
+                }
+
+                if let Some(loot) = &part.loot {
+                    // the map of symbols has no order, so always drop on the same corner
+                    if let Some(part_pos) = part.symbol_map.keys().min_by_key(|p| (p.y, p.x)) {
+                        s_builder.drop_item(pos.as_point() + *part_pos, loot.clone());
+                    }
+                }
+
+                let name = viewables
+                    .get(ent)
+                    .map_or("boss".to_string(), |view| view.name.to_lowercase());
+                log.add(format!(
+                    "The {}'s {} breaks off",
+                    name,
+                    part.name.to_lowercase()
+                ));
+
+                if bosses.get(ent).is_some() {
+                    if let Some(sched) = schedulables.get_mut(ent) {
+                        sched.base = std::cmp::max(sched.base - 1, crate::consts::BOSS_MIN_SPEED);
                     }
+
+                    log.add(format!("The {} grows frenzied", name));
                 }
             }
         }
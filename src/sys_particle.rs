@@ -3,20 +3,25 @@ use rltk::{FontCharType, Point, Rltk};
 use specs::prelude::*;
 
 pub fn cleanup_particles(ecs: &mut World, ctx: &Rltk) {
-    let dead_particles = update_lifetimes(ecs, ctx);
+    expire_particles(ecs, ctx.frame_time_ms);
+}
+
+// age particles by a fixed amount of time, for when there is no rltk context to time frames
+pub fn expire_particles(ecs: &mut World, frame_time_ms: f32) {
+    let dead_particles = update_lifetimes(ecs, frame_time_ms);
 
     for dead in dead_particles.iter() {
         ecs.delete_entity(*dead).expect("Failed to delete particle");
     }
 }
 
-fn update_lifetimes(ecs: &mut World, ctx: &Rltk) -> Vec<Entity> {
+fn update_lifetimes(ecs: &mut World, frame_time_ms: f32) -> Vec<Entity> {
     let mut dead_particles = Vec::new();
     let mut particles = ecs.write_storage::<ParticleLifetime>();
     let entities = ecs.entities();
 
     for (ent, lifetime) in (&entities, &mut particles).join() {
-        lifetime.remaining -= ctx.frame_time_ms;
+        lifetime.remaining -= frame_time_ms;
         if lifetime.remaining < 0.0 {
             dead_particles.push(ent);
         }
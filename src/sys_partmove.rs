@@ -36,7 +36,13 @@ impl<'a> System<'a> for PartMoveSystem {
                             // each entity can only be pushed once
                             pushed_ents.push(*map_ent);
                             pushes
-                                .insert(*map_ent, crate::PushForce { delta: *dir })
+                                .insert(
+                                    *map_ent,
+                                    crate::PushForce {
+                                        delta: *dir,
+                                        impact_damage: 0,
+                                    },
+                                )
                                 .expect("Failed to insert push caused by part movement");
                         }
                     }
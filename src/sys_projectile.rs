@@ -26,7 +26,7 @@ impl<'a> System<'a> for ProjectileSystem {
 
             if attack_path.index >= attack_path.path.len() {
                 if let Some(final_point) = attack_path.path.last() {
-                    finished.push((ent, attack_path.on_hit, *final_point));
+                    finished.push((ent, attack_path.on_hit, attack_path.bonus, *final_point));
                 }
                 continue;
             }
@@ -35,21 +35,22 @@ impl<'a> System<'a> for ProjectileSystem {
             let point_index = map.point2d_to_index(point);
 
             if !map.is_tile_valid(point.x, point.y) {
-                finished.push((ent, attack_path.on_hit, point));
+                finished.push((ent, attack_path.on_hit, attack_path.bonus, point));
             } else if let Some(aff_ent) = map.creature_map.get(&point_index) {
                 // Be careful around self damage with multi-tile
                 // We can't check *aff_ent != ent, since ent refers
                 // to the projectile here
-                finished.push((ent, attack_path.on_hit, point));
+                finished.push((ent, attack_path.on_hit, attack_path.bonus, point));
             }
         }
 
-        for (ent, next_attack, impact_loc) in finished.iter() {
+        for (ent, next_attack, bonus, impact_loc) in finished.iter() {
             attack_paths.remove(*ent);
 
             let intent = crate::AttackIntent {
                 main: *next_attack,
                 loc: *impact_loc,
+                bonus: *bonus,
             };
 
             let frame = crate::get_frame_data(*next_attack);
@@ -1,9 +1,11 @@
 use rltk::Algorithm2D;
 use specs::prelude::*;
+use std::collections::VecDeque;
 
 pub struct PushSystem;
 
-const MAX_ITERS: i32 = 100;
+// a push can knock into a creature that knocks into another; stop following the chain after this
+const MAX_CHAIN: usize = 100;
 
 impl<'a> System<'a> for PushSystem {
     type SystemData = (
@@ -12,137 +14,601 @@ impl<'a> System<'a> for PushSystem {
         WriteStorage<'a, crate::Position>,
         ReadStorage<'a, crate::MultiTile>,
         WriteStorage<'a, crate::Viewshed>,
+        WriteStorage<'a, crate::Stunned>,
+        WriteStorage<'a, crate::Health>,
+        ReadStorage<'a, crate::Invulnerable>,
+        WriteStorage<'a, crate::Fragile>,
+        WriteStorage<'a, crate::StatusEffects>,
+        ReadStorage<'a, crate::Viewable>,
+        ReadExpect<'a, Entity>,
         WriteExpect<'a, crate::Map>,
+        WriteExpect<'a, crate::ParticleBuilder>,
+        WriteExpect<'a, crate::Noises>,
+        WriteExpect<'a, crate::GameLog>,
+        WriteExpect<'a, crate::MissionInfo>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut pushes, mut positions, multitiles, mut viewsheds, mut map) = data;
-
-        for (ent, force, pos, multis, view) in (
-            &entities,
-            &pushes,
-            &mut positions,
-            (&multitiles).maybe(),
-            (&mut viewsheds).maybe(),
-        )
+        let (
+            entities,
+            mut pushes,
+            mut positions,
+            multitiles,
+            mut viewsheds,
+            mut stuns,
+            mut healths,
+            invulns,
+            mut breakables,
+            mut statuses,
+            viewables,
+            player,
+            mut map,
+            mut p_builder,
+            mut noises,
+            mut log,
+            mut mission,
+        ) = data;
+
+        let mut queue = (&entities, &pushes)
             .join()
-        {
-            let final_dest = match multis {
-                None => apply_forces(ent, force, pos, &mut *map),
-                Some(multi) => apply_forces_to_multi(ent, force, pos, multi, &mut *map),
-            };
+            .map(|(ent, force)| (ent, force.clone()))
+            .collect::<VecDeque<_>>();
+        pushes.clear();
 
-            *pos = crate::Position {
-                x: final_dest.x,
-                y: final_dest.y,
+        let mut resolved = 0;
+        while let Some((ent, force)) = queue.pop_front() {
+            if resolved >= MAX_CHAIN {
+                break;
+            }
+            resolved += 1;
+
+            let start = match positions.get(ent) {
+                None => continue,
+                Some(pos) => pos.as_point(),
             };
+            let multi = multitiles.get(ent);
+            let footprint = crate::get_footprint(multi);
+            let outcome = resolve_push(ent, &footprint, start, force.delta, &map);
+
+            // the resolver has already checked the way is clear
+            if outcome.dest != start {
+                map.place_creature(ent, start, outcome.dest, multi);
+                if let Some(pos) = positions.get_mut(ent) {
+                    *pos = crate::Position {
+                        x: outcome.dest.x,
+                        y: outcome.dest.y,
+                    };
+                }
 
-            if let Some(view) = view {
-                view.dirty = true;
+                if let Some(view) = viewsheds.get_mut(ent) {
+                    view.dirty = true;
+                }
             }
-        }
 
-        pushes.clear();
+            let mut impacts = outcome.impacts;
+            let mut hit = Vec::new();
+            let is_visible = map.visible_tiles[map.point2d_to_index(outcome.dest)];
+
+            // whatever we ran into takes the rest of the force, except fragile walls, which just break
+            if let Some((other, delta)) = outcome.chained {
+                impacts += 1;
+                if let Some(fragile) = breakables.get_mut(other) {
+                    fragile.was_hit = true;
+                } else {
+                    hit.push((other, 1));
+                    queue.push_back((
+                        other,
+                        crate::PushForce {
+                            delta,
+                            impact_damage: force.impact_damage,
+                        },
+                    ));
+                }
+            }
+
+            if impacts > 0 {
+                hit.push((ent, impacts));
+                noises.make(outcome.dest, crate::consts::IMPACT_NOISE_RADIUS);
+
+                if is_visible {
+                    if let Some(message) = knocked_into(ent, *player, &viewables, "something") {
+                        log.add(message);
+                    }
+                }
+            }
+
+            if let Some(hazard) = outcome.hazard {
+                let what = match hazard {
+                    crate::Hazard::Lava => "the lava",
+                    crate::Hazard::Spikes => "the spikes",
+                    crate::Hazard::Water => "the water",
+                };
+
+                if is_visible {
+                    if let Some(message) = knocked_into(ent, *player, &viewables, what) {
+                        log.add(message);
+                    }
+                }
+
+                match hazard {
+                    crate::Hazard::Lava | crate::Hazard::Spikes => {
+                        if invulns.get(ent).is_none() {
+                            if let Some(health) = healths.get_mut(ent) {
+                                health.current -= if hazard == crate::Hazard::Lava {
+                                    crate::consts::LAVA_DAMAGE
+                                } else {
+                                    crate::consts::SPIKE_DAMAGE
+                                };
+                            }
+
+                            if hazard == crate::Hazard::Lava {
+                                crate::status::apply_status(
+                                    &mut statuses,
+                                    ent,
+                                    crate::status::StatusKind::Burn,
+                                    crate::consts::TERRAIN_BURN_DURATION,
+                                );
+                            }
+                        }
+                    }
+                    crate::Hazard::Water => {
+                        stuns
+                            .insert(
+                                ent,
+                                crate::Stunned {
+                                    duration: crate::consts::WALL_HIT_STUN_DURATION,
+                                },
+                            )
+                            .ok();
+
+                        if ent != *player {
+                            mission.dunked += 1;
+                        }
+                    }
+                }
+            }
+
+            for (hit_ent, impacts) in hit {
+                stuns
+                    .insert(
+                        hit_ent,
+                        crate::Stunned {
+                            duration: crate::consts::WALL_HIT_STUN_DURATION,
+                        },
+                    )
+                    .ok();
+                p_builder.make_hit_particle(hit_ent);
+
+                if force.impact_damage > 0 && invulns.get(hit_ent).is_none() {
+                    if let Some(health) = healths.get_mut(hit_ent) {
+                        health.current -= force.impact_damage * impacts;
+                    }
+                }
+            }
+        }
     }
 }
 
-fn apply_forces(
+// "You are knocked into the water", or the same about a creature we know the name of
+fn knocked_into(
     ent: Entity,
-    force: &crate::PushForce,
-    pos: &crate::Position,
-    map: &mut crate::Map,
-) -> rltk::Point {
-    let mut prev_point;
-    let mut new_point = pos.as_point();
-    let mut applied_force = force.delta;
-
-    for _ in 0..MAX_ITERS {
-        prev_point = new_point;
-        new_point = prev_point + applied_force;
-
-        match calc_dest_force(ent, applied_force, prev_point, new_point, map) {
-            None => return new_point,
-            Some((updated_point, new_force)) => {
-                applied_force = new_force;
-                new_point = updated_point;
-            }
-        }
+    player: Entity,
+    viewables: &ReadStorage<crate::Viewable>,
+    what: &str,
+) -> Option<String> {
+    if ent == player {
+        return Some(format!("You are knocked into {}", what));
     }
 
-    dbg!("can't find a place to move to");
-    rltk::Point::zero()
+    viewables
+        .get(ent)
+        .map(|view| format!("A {} is knocked into {}", view.name.to_lowercase(), what))
+}
+
+// what a push ran into
+enum Impact {
+    Clear,
+    Wall,
+    Creature(Entity),
+    // terrain we can be knocked onto, but not any further
+    Hazard(crate::Hazard),
+}
+
+pub struct PushOutcome {
+    pub dest: rltk::Point,
+    // walls bounced off on the way
+    pub impacts: i32,
+    // the creature we stopped against, and the force passed on to it
+    pub chained: Option<(Entity, rltk::Point)>,
+    // the terrain we landed in, which stopped us
+    pub hazard: Option<crate::Hazard>,
 }
 
-fn calc_dest_force(
-    _ent: Entity,
-    applied_force: rltk::Point,
-    prev_point: rltk::Point,
-    new_point: rltk::Point,
-    map: &mut crate::Map,
-) -> Option<(rltk::Point, rltk::Point)> {
-    let new_idx = map.get_index(new_point.x, new_point.y);
-
-    // if we can fit in the new tile, we are done
-    if !map.blocked_tiles[new_idx] {
-        return None;
-    }
-
-    // TODO: assuming forces at most 1 tile (will also need to update collision checking sys_partmove to support higher forces)
-    let refl_h_force = rltk::Point::new(-applied_force.x, applied_force.y);
-    let refl_h_pos = new_point + refl_h_force;
-    let refl_h_idx = map.get_index(refl_h_pos.x, refl_h_pos.y);
-    if !map.blocked_tiles[refl_h_idx] {
-        return Some((new_point, refl_h_force));
-    }
-
-    let refl_v_force = rltk::Point::new(applied_force.x, -applied_force.y);
-    let refl_v_pos = new_point + refl_v_force;
-    let refl_v_idx = map.get_index(refl_v_pos.x, refl_v_pos.y);
-    if !map.blocked_tiles[refl_v_idx] {
-        return Some((new_point, refl_v_force));
-    }
-
-    // Move to an open adjacent square
-    let mut open_list = Vec::new();
-    for dx in -1..=1 {
-        for dy in -1..1 {
-            if dx == 0 && dy == 0 {
-                continue;
+// Traces a push a tile at a time along its force. Walls reflect the part of the force that ran
+// into them, while running into a creature stops us and hands it the rest of the force
+pub fn resolve_push(
+    ent: Entity,
+    footprint: &[rltk::Point],
+    start: rltk::Point,
+    delta: rltk::Point,
+    map: &crate::Map,
+) -> PushOutcome {
+    let steps = std::cmp::max(delta.x.abs(), delta.y.abs());
+    let offset_at = |force: rltk::Point, step: i32| {
+        rltk::Point::new(force.x * step / steps, force.y * step / steps)
+    };
+
+    let mut outcome = PushOutcome {
+        dest: start,
+        impacts: 0,
+        chained: None,
+        hazard: None,
+    };
+    let mut force = delta;
+    let mut segment_start = start;
+    let mut segment_step = 0;
+    let mut last_step = rltk::Point::zero();
+
+    for step in 0..steps {
+        let next_offset = offset_at(force, segment_step + 1);
+        let next = segment_start + next_offset;
+        let step_dir = next_offset - offset_at(force, segment_step);
+
+        match find_impact(ent, footprint, next, map) {
+            Impact::Clear => {
+                outcome.dest = next;
+                segment_step += 1;
+                last_step = step_dir;
+            }
+            Impact::Creature(other) => {
+                let remaining = steps - step;
+                outcome.chained = Some((
+                    other,
+                    rltk::Point::new(force.x * remaining / steps, force.y * remaining / steps),
+                ));
+                return outcome;
+            }
+            Impact::Hazard(hazard) => {
+                outcome.dest = next;
+                outcome.hazard = Some(hazard);
+                return outcome;
             }
+            Impact::Wall => {
+                outcome.impacts += 1;
 
-            let new_x = prev_point.x + dx;
-            let new_y = prev_point.y + dy;
+                // bounce off whichever side we hit; a corner sends us straight back
+                let dest = outcome.dest;
+                let hit_x = step_dir.x != 0
+                    && matches!(
+                        find_impact(ent, footprint, dest + rltk::Point::new(step_dir.x, 0), map),
+                        Impact::Wall | Impact::Creature(_)
+                    );
+                let hit_y = step_dir.y != 0
+                    && matches!(
+                        find_impact(ent, footprint, dest + rltk::Point::new(0, step_dir.y), map),
+                        Impact::Wall | Impact::Creature(_)
+                    );
 
-            if map.in_bounds(rltk::Point::new(new_x, new_y)) {
-                let new_map_idx = map.get_index(new_x, new_y);
-                if !map.blocked_tiles[new_map_idx] {
-                    open_list.push(rltk::Point::new(dx, dy));
+                if hit_x || !hit_y {
+                    force.x = -force.x;
                 }
+                if hit_y || !hit_x {
+                    force.y = -force.y;
+                }
+
+                segment_start = dest;
+                segment_step = 0;
             }
         }
     }
 
-    if !open_list.is_empty() {
-        open_list.sort_by(|d1, d2| {
-            let dist1 = rltk::DistanceAlg::Manhattan.distance2d(applied_force, *d1);
-            let dist2 = rltk::DistanceAlg::Manhattan.distance2d(applied_force, *d2);
-            dist1.partial_cmp(&dist2).unwrap()
-        });
+    // ice keeps things sliding the same way until they run into something
+    if outcome.impacts == 0 && last_step != rltk::Point::zero() {
+        for _ in 0..map.width + map.height {
+            let index = map.point2d_to_index(outcome.dest);
+            if map.tiles[index] != crate::TileType::Ice {
+                break;
+            }
 
-        return Some((prev_point, *open_list.first().unwrap()));
+            let next = outcome.dest + last_step;
+            match find_impact(ent, footprint, next, map) {
+                Impact::Clear => outcome.dest = next,
+                Impact::Hazard(hazard) => {
+                    outcome.dest = next;
+                    outcome.hazard = Some(hazard);
+                    break;
+                }
+                Impact::Wall | Impact::Creature(_) => break,
+            }
+        }
     }
 
-    // nothing open nearby, check again at one of the reflected positions
-    Some((prev_point, refl_h_force))
+    outcome
 }
 
-// TODO: Not even gonna think about this right now
-fn apply_forces_to_multi(
+fn find_impact(
     ent: Entity,
-    force: &crate::PushForce,
-    pos: &crate::Position,
-    multi: &crate::MultiTile,
-    map: &mut crate::Map,
-) -> rltk::Point {
-    pos.as_point()
+    footprint: &[rltk::Point],
+    point: rltk::Point,
+    map: &crate::Map,
+) -> Impact {
+    let mut hazard = None;
+
+    for offset in footprint {
+        let tile = point + *offset;
+        if !map.in_bounds(tile) {
+            return Impact::Wall;
+        }
+
+        // our own tiles are blocked too, so check who is there before the tile itself
+        let idx = map.point2d_to_index(tile);
+        match map.creature_map.get(&idx) {
+            Some(other) if *other != ent => return Impact::Creature(*other),
+            Some(_) => {}
+            None => match map.get_hazard(idx) {
+                Some(tile_hazard) => hazard = hazard.or(Some(tile_hazard)),
+                None if map.blocked_tiles[idx] => return Impact::Wall,
+                None => {}
+            },
+        }
+    }
+
+    // walls and creatures anywhere under the body stop it before it lands in anything
+    match hazard {
+        Some(hazard) => Impact::Hazard(hazard),
+        None => Impact::Clear,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rltk::Point;
+
+    // a room of floor with a wall all the way round
+    fn open_map(width: i32, height: i32) -> crate::Map {
+        let mut rng = rltk::RandomNumberGenerator::seeded(0);
+        let mut map = crate::Map::new(width, height, 0, "test", "#808080", &mut rng);
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let index = map.get_index(x, y);
+                map.tiles[index] = crate::TileType::Floor;
+            }
+        }
+        map.set_blocked_tiles();
+        map
+    }
+
+    fn place(map: &mut crate::Map, ent: Entity, point: Point) {
+        let index = map.point2d_to_index(point);
+        map.track_creature(ent, index, None);
+    }
+
+    #[test]
+    fn corner_sends_push_straight_back() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let mut map = open_map(7, 7);
+        place(&mut map, ent, Point::new(4, 4));
+
+        let outcome = resolve_push(
+            ent,
+            &[Point::zero()],
+            Point::new(4, 4),
+            Point::new(3, 3),
+            &map,
+        );
+
+        assert_eq!(outcome.dest, Point::new(4, 4));
+        assert_eq!(outcome.impacts, 1);
+        assert!(outcome.chained.is_none());
+    }
+
+    #[test]
+    fn diagonal_push_reflects_off_wall() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let mut map = open_map(7, 7);
+        place(&mut map, ent, Point::new(2, 4));
+
+        // the floor turns the downward part of the push round, the rest carries on
+        let outcome = resolve_push(
+            ent,
+            &[Point::zero()],
+            Point::new(2, 4),
+            Point::new(3, 3),
+            &map,
+        );
+
+        assert_eq!(outcome.dest, Point::new(4, 4));
+        assert_eq!(outcome.impacts, 1);
+    }
+
+    #[test]
+    fn creature_takes_rest_of_force() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let other = world.create_entity().build();
+        let mut map = open_map(9, 5);
+        place(&mut map, ent, Point::new(2, 2));
+        place(&mut map, other, Point::new(4, 2));
+
+        let outcome = resolve_push(
+            ent,
+            &[Point::zero()],
+            Point::new(2, 2),
+            Point::new(4, 0),
+            &map,
+        );
+
+        assert_eq!(outcome.dest, Point::new(3, 2));
+        assert_eq!(outcome.impacts, 0);
+        assert_eq!(outcome.chained, Some((other, Point::new(3, 0))));
+    }
+
+    #[test]
+    fn large_body_bounces_off_wall() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let mut map = open_map(7, 7);
+        let footprint = [
+            Point::zero(),
+            Point::new(1, 0),
+            Point::new(0, 1),
+            Point::new(1, 1),
+        ];
+        for offset in footprint {
+            place(&mut map, ent, Point::new(2, 2) + offset);
+        }
+
+        // the right edge of the body reaches the wall a tile before its origin would
+        let outcome = resolve_push(ent, &footprint, Point::new(2, 2), Point::new(3, 0), &map);
+
+        assert_eq!(outcome.dest, Point::new(4, 2));
+        assert_eq!(outcome.impacts, 1);
+    }
+
+    #[test]
+    fn push_stops_in_lava() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let mut map = open_map(9, 5);
+        let lava = map.get_index(4, 2);
+        map.tiles[lava] = crate::TileType::Lava;
+        map.set_blocked_tiles();
+        place(&mut map, ent, Point::new(2, 2));
+
+        let outcome = resolve_push(
+            ent,
+            &[Point::zero()],
+            Point::new(2, 2),
+            Point::new(4, 0),
+            &map,
+        );
+
+        assert_eq!(outcome.dest, Point::new(4, 2));
+        assert_eq!(outcome.hazard, Some(crate::Hazard::Lava));
+    }
+
+    #[test]
+    fn deep_water_is_a_wall() {
+        let mut world = World::new();
+        let ent = world.create_entity().build();
+        let mut map = open_map(9, 5);
+        let water = map.get_index(4, 2);
+        map.tiles[water] = crate::TileType::Water;
+        map.set_blocked_tiles();
+        place(&mut map, ent, Point::new(2, 2));
+
+        let outcome = resolve_push(
+            ent,
+            &[Point::zero()],
+            Point::new(2, 2),
+            Point::new(4, 0),
+            &map,
+        );
+
+        assert_ne!(outcome.dest, Point::new(4, 2));
+        assert_eq!(outcome.impacts, 1);
+        assert_eq!(outcome.hazard, None);
+    }
+
+    // a row of creatures pushed from one end, with the system resolving the chain. The last one
+    // can be made a fragile wall instead
+    fn push_row(length: i32, fragile_end: bool) -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let mut system = PushSystem;
+        System::setup(&mut system, &mut world);
+
+        let mut map = open_map(length + 4, 3);
+        let player = world.create_entity().build();
+        let mut row = Vec::new();
+        for x in 1..=length {
+            let ent = world
+                .create_entity()
+                .with(crate::Position { x, y: 1 })
+                .build();
+            place(&mut map, ent, Point::new(x, 1));
+            row.push(ent);
+        }
+
+        if fragile_end {
+            world
+                .write_storage::<crate::Fragile>()
+                .insert(
+                    row[row.len() - 1],
+                    crate::Fragile {
+                        lifetime: 10,
+                        was_hit: false,
+                    },
+                )
+                .ok();
+        }
+
+        world
+            .write_storage::<crate::PushForce>()
+            .insert(
+                row[0],
+                crate::PushForce {
+                    delta: Point::new(1, 0),
+                    impact_damage: 0,
+                },
+            )
+            .ok();
+
+        world.insert(map);
+        world.insert(player);
+        world.insert(crate::ParticleBuilder::new());
+        world.insert(crate::Noises::new());
+        world.insert(crate::MissionInfo::new());
+        world.insert(crate::GameLog {
+            entries: Vec::new(),
+            pending: None,
+            dirty: false,
+        });
+
+        system.run_now(&world);
+        (world, row)
+    }
+
+    #[test]
+    fn crowd_passes_push_down_the_line() {
+        let (world, row) = push_row(3, false);
+        let positions = world.read_storage::<crate::Position>();
+        let stuns = world.read_storage::<crate::Stunned>();
+
+        // everyone knocks into the next, and only the last one has room to move
+        assert_eq!(positions.get(row[0]).unwrap().x, 1);
+        assert_eq!(positions.get(row[1]).unwrap().x, 2);
+        assert_eq!(positions.get(row[2]).unwrap().x, 4);
+        assert!(stuns.get(row[0]).is_some());
+        assert!(stuns.get(row[1]).is_some());
+    }
+
+    #[test]
+    fn long_chain_is_cut_off() {
+        let length = MAX_CHAIN as i32 + 10;
+        let (world, row) = push_row(length, false);
+        let positions = world.read_storage::<crate::Position>();
+        let stuns = world.read_storage::<crate::Stunned>();
+
+        // the last push resolved hands its force on, but that one is never followed
+        assert!(stuns.get(row[MAX_CHAIN]).is_some());
+        assert!(stuns.get(row[MAX_CHAIN + 1]).is_none());
+        for (x, ent) in row.iter().enumerate() {
+            assert_eq!(positions.get(*ent).unwrap().x, x as i32 + 1);
+        }
+    }
+
+    #[test]
+    fn fragile_wall_breaks_without_moving() {
+        let (world, row) = push_row(3, true);
+        let positions = world.read_storage::<crate::Position>();
+        let fragiles = world.read_storage::<crate::Fragile>();
+
+        assert!(fragiles.get(row[2]).unwrap().was_hit);
+        for (x, ent) in row.iter().enumerate() {
+            assert_eq!(positions.get(*ent).unwrap().x, x as i32 + 1);
+        }
+    }
 }
@@ -0,0 +1,92 @@
+use crate::attack_type;
+use specs::prelude::*;
+
+// Attacks that were just started open a reaction window for whoever they are aimed at. The player
+// always gets one, while enemies need a CanReactFlag
+pub struct ReactionSystem;
+
+impl<'a> System<'a> for ReactionSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteStorage<'a, crate::CanActFlag>,
+        ReadStorage<'a, crate::CanReactFlag>,
+        ReadStorage<'a, crate::AttackIntent>,
+        ReadStorage<'a, crate::FrameData>,
+        ReadStorage<'a, crate::Position>,
+        WriteStorage<'a, crate::Schedulable>,
+        ReadStorage<'a, crate::Stunned>,
+        ReadStorage<'a, crate::Viewable>,
+        WriteExpect<'a, crate::GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player,
+            mut can_act,
+            can_react,
+            attacks,
+            frames,
+            positions,
+            mut schedulables,
+            stuns,
+            viewables,
+            mut log,
+        ) = data;
+        let mut reactions = Vec::new();
+
+        for (attacker, intent, frame) in (&entities, &attacks, &frames).join() {
+            // only telegraphed attacks can be reacted to, and only once as they start
+            if frame.current != 0 || frame.startup == 0 {
+                continue;
+            }
+
+            let targets = attack_type::each_intent_target(intent);
+            for (ent, pos) in (&entities, &positions).join() {
+                if ent == attacker || !targets.contains(&pos.as_point()) {
+                    continue;
+                }
+
+                if ent != *player && can_react.get(ent).is_none() {
+                    continue;
+                }
+
+                // someone busy with their own attack or reeling from a hit can't respond
+                if can_act.get(ent).is_some()
+                    || frames.get(ent).is_some()
+                    || stuns.get(ent).is_some()
+                    || reactions.iter().any(|(reactor, _)| *reactor == ent)
+                {
+                    continue;
+                }
+
+                reactions.push((ent, attacker));
+            }
+        }
+
+        for (reactor, attacker) in reactions {
+            can_act
+                .insert(
+                    reactor,
+                    crate::CanActFlag {
+                        is_reaction: true,
+                        reaction_target: Some(attacker),
+                    },
+                )
+                .expect("Failed to insert CanActFlag");
+
+            if reactor == *player {
+                if let Some(view) = viewables.get(attacker) {
+                    log.add(format!(
+                        "The {} winds up an attack, react!",
+                        view.name.to_lowercase()
+                    ));
+                }
+            } else if let Some(sched) = schedulables.get_mut(reactor) {
+                // enemies always take their reaction, so it comes out of their next turn
+                sched.current += sched.base;
+            }
+        }
+    }
+}
@@ -11,18 +11,29 @@ pub struct SpawnRequest {
 
 pub struct Spawner {
     requests: Vec<SpawnRequest>,
+    // items are built from the item table, which needs the whole world rather than a system
+    drops: Vec<(rltk::Point, String)>,
 }
 
 impl Spawner {
     pub fn new() -> Spawner {
         Spawner {
             requests: Vec::new(),
+            drops: Vec::new(),
         }
     }
 
     pub fn spawn(&mut self, request: SpawnRequest) {
         self.requests.push(request);
     }
+
+    pub fn drop_item(&mut self, position: rltk::Point, name: String) {
+        self.drops.push((position, name));
+    }
+
+    pub fn take_drops(&mut self) -> Vec<(rltk::Point, String)> {
+        std::mem::take(&mut self.drops)
+    }
 }
 
 pub struct SpawnSystem;
@@ -0,0 +1,76 @@
+use crate::status::StatusKind;
+use crate::TileType;
+use rltk::Algorithm2D;
+use specs::prelude::*;
+
+// Burns down and spreads grass fires, and keeps anyone standing in fire or lava burning
+pub struct TerrainSystem;
+
+impl<'a> System<'a> for TerrainSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, crate::Map>,
+        ReadStorage<'a, crate::Position>,
+        ReadStorage<'a, crate::Health>,
+        WriteStorage<'a, crate::StatusEffects>,
+        WriteExpect<'a, crate::GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, player, mut map, positions, healths, mut statuses, mut log) = data;
+        let mut spreading = Vec::new();
+        let mut burnt_out = Vec::new();
+
+        for (index, remaining) in map.fires.iter_mut() {
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                burnt_out.push(*index);
+            } else if *remaining % crate::consts::FIRE_SPREAD_DELAY == 0 {
+                spreading.push(*index);
+            }
+        }
+
+        for index in burnt_out {
+            map.fires.remove(&index);
+            map.tiles[index] = TileType::Floor;
+        }
+
+        for index in spreading {
+            let point = map.index_to_point2d(index);
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let next = rltk::Point::new(point.x + dx, point.y + dy);
+                if map.in_bounds(next) {
+                    let next_index = map.point2d_to_index(next);
+                    map.ignite(next_index);
+                }
+            }
+        }
+
+        for (ent, pos, _) in (&entities, &positions, &healths).join() {
+            let index = map.get_index(pos.x, pos.y);
+            if map.tiles[index] != TileType::Lava && map.tiles[index] != TileType::BurningGrass {
+                continue;
+            }
+
+            // only catch once, rather than piling on stacks every tick
+            let is_burning = statuses
+                .get(ent)
+                .is_some_and(|effects| effects.get(StatusKind::Burn).is_some());
+            if is_burning {
+                continue;
+            }
+
+            crate::status::apply_status(
+                &mut statuses,
+                ent,
+                StatusKind::Burn,
+                crate::consts::TERRAIN_BURN_DURATION,
+            );
+
+            if ent == *player {
+                log.add("You catch fire");
+            }
+        }
+    }
+}
@@ -13,6 +13,9 @@ impl<'a> System<'a> for TurnSystem {
         ReadExpect<'a, Entity>,
         WriteStorage<'a, crate::Invulnerable>,
         WriteStorage<'a, crate::Stamina>,
+        WriteStorage<'a, crate::StatusEffects>,
+        WriteStorage<'a, crate::Health>,
+        WriteExpect<'a, crate::GameLog>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -25,6 +28,9 @@ impl<'a> System<'a> for TurnSystem {
             player,
             mut invulns,
             mut stams,
+            mut statuses,
+            mut healths,
+            mut log,
         ) = data;
         assert!(*game_state == RunState::Running);
         if can_act.get(*player).is_some() {
@@ -33,6 +39,26 @@ impl<'a> System<'a> for TurnSystem {
         }
 
         let mut invuln_over = Vec::new();
+        let mut status_over = Vec::new();
+
+        for (ent, effects, sched) in (&entities, &mut statuses, &mut schedulables).join() {
+            sched.delta = effects.get_delta();
+
+            let damage = effects.tick();
+            if damage > 0 {
+                Self::take_status_damage(ent, damage, &player, &mut healths, &mut log);
+            }
+
+            // the last effect wearing off puts the schedule back to normal
+            if effects.effects.is_empty() {
+                sched.delta = 1;
+                status_over.push(ent);
+            }
+        }
+
+        for done in status_over {
+            statuses.remove(done);
+        }
 
         for (ent, sched, _pos, invuln, stam) in (
             &entities,
@@ -66,6 +92,14 @@ impl<'a> System<'a> for TurnSystem {
             }
 
             sched.current += sched.base;
+
+            if let Some(effects) = statuses.get(ent) {
+                let damage = effects.get_bleed_damage();
+                if damage > 0 {
+                    Self::take_status_damage(ent, damage, &player, &mut healths, &mut log);
+                }
+            }
+
             can_act
                 .insert(
                     ent,
@@ -82,3 +116,22 @@ impl<'a> System<'a> for TurnSystem {
         }
     }
 }
+
+impl TurnSystem {
+    fn take_status_damage(
+        ent: Entity,
+        damage: i32,
+        player: &Entity,
+        healths: &mut WriteStorage<crate::Health>,
+        log: &mut crate::GameLog,
+    ) {
+        if let Some(health) = healths.get_mut(ent) {
+            health.current -= damage;
+
+            // enemies show it on their health bars
+            if ent == *player {
+                log.add(format!("You suffer {} damage", damage));
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::weapon::Weapon;
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+const ARMOR_COST: u32 = 10;
+const MAX_ARMOR_LEVEL: u32 = 2;
+const ABILITY_COST: u32 = 5;
+const MAX_ABILITY_LEVEL: u32 = 2;
+// frames of startup taken off for each level
+const STARTUP_PER_LEVEL: u32 = 2;
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum AbilityUpgrade {
+    Stamina,
+    Startup,
+    Radius,
+}
+
+const ABILITY_UPGRADES: [AbilityUpgrade; 3] = [
+    AbilityUpgrade::Stamina,
+    AbilityUpgrade::Startup,
+    AbilityUpgrade::Radius,
+];
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Upgrade {
+    Armor,
+    Ability { index: usize, kind: AbilityUpgrade },
+}
+
+// everything the blacksmith can work on for the given weapon
+pub fn get_upgrades(weapon: &Weapon) -> Vec<Upgrade> {
+    let mut upgrades = vec![Upgrade::Armor];
+    let base_weapon = weapon::get_weapon(weapon.weapon_type);
+
+    for (index, ability) in base_weapon.abilities.iter().enumerate() {
+        for kind in ABILITY_UPGRADES {
+            if get_max_ability_level(ability, kind) > 0 {
+                upgrades.push(Upgrade::Ability { index, kind });
+            }
+        }
+    }
+
+    upgrades
+}
+
+fn get_max_ability_level(ability: &AttackData, kind: AbilityUpgrade) -> u32 {
+    // abilities always cost some stamina and take at least a frame to come out
+    let max_level = match kind {
+        AbilityUpgrade::Stamina => std::cmp::max(ability.stam_cost - 1, 0) as u32,
+        AbilityUpgrade::Startup => {
+            attack_type::get_startup(ability.attack_type).saturating_sub(1) / STARTUP_PER_LEVEL
+        }
+        AbilityUpgrade::Radius => {
+            if has_radius(ability.attack_type) {
+                MAX_ABILITY_LEVEL
+            } else {
+                0
+            }
+        }
+    };
+
+    std::cmp::min(max_level, MAX_ABILITY_LEVEL)
+}
+
+// projectiles count as having a radius if they explode into one
+fn has_radius(attack_type: AttackType) -> bool {
+    attack_type::get_attack_shape(attack_type).has_size()
+        || attack_type::get_attack_traits(attack_type)
+            .iter()
+            .any(|tr| match tr {
+                AttackTrait::FollowsPath { on_hit, .. } => {
+                    attack_type::get_attack_shape(*on_hit).has_size()
+                }
+                _ => false,
+            })
+}
+
+pub fn get_level(gs: &State, upgrade: Upgrade) -> u32 {
+    match upgrade {
+        Upgrade::Armor => gs.player_inventory.armor_level,
+        Upgrade::Ability { index, kind } => {
+            let bonus = gs
+                .player_inventory
+                .get_bonus(gs.player_weapon.weapon_type, index);
+
+            match kind {
+                AbilityUpgrade::Stamina => bonus.stamina as u32,
+                AbilityUpgrade::Startup => bonus.startup / STARTUP_PER_LEVEL,
+                AbilityUpgrade::Radius => bonus.radius as u32,
+            }
+        }
+    }
+}
+
+pub fn get_max_level(gs: &State, upgrade: Upgrade) -> u32 {
+    match upgrade {
+        Upgrade::Armor => MAX_ARMOR_LEVEL,
+        Upgrade::Ability { index, kind } => {
+            let base_weapon = weapon::get_weapon(gs.player_weapon.weapon_type);
+            get_max_ability_level(&base_weapon.abilities[index], kind)
+        }
+    }
+}
+
+// each level costs more than the last
+pub fn get_cost(gs: &State, upgrade: Upgrade) -> u32 {
+    let base_cost = match upgrade {
+        Upgrade::Armor => ARMOR_COST,
+        Upgrade::Ability { .. } => ABILITY_COST,
+    };
+
+    base_cost * (get_level(gs, upgrade) + 1)
+}
+
+pub fn get_name(gs: &State, upgrade: Upgrade) -> String {
+    match upgrade {
+        Upgrade::Armor => "Armor".to_string(),
+        Upgrade::Ability { index, kind } => {
+            let ability_name = &gs.player_weapon.abilities[index].name;
+            let kind_name = match kind {
+                AbilityUpgrade::Stamina => "cost",
+                AbilityUpgrade::Startup => "speed",
+                AbilityUpgrade::Radius => "radius",
+            };
+
+            format!("{} {}", ability_name, kind_name)
+        }
+    }
+}
+
+pub fn get_description(upgrade: Upgrade) -> String {
+    match upgrade {
+        Upgrade::Armor => "Take 1 less damage from heavy hits".to_string(),
+        Upgrade::Ability { kind, .. } => match kind {
+            AbilityUpgrade::Stamina => "Costs 1 less stamina".to_string(),
+            AbilityUpgrade::Startup => format!("Comes out {} frames sooner", STARTUP_PER_LEVEL),
+            AbilityUpgrade::Radius => "Hits 1 tile further out".to_string(),
+        },
+    }
+}
+
+pub fn buy_upgrade(gs: &mut State, upgrade: Upgrade) {
+    if get_level(gs, upgrade) >= get_max_level(gs, upgrade) {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add("That can't be improved any further");
+        return;
+    }
+
+    let cost = get_cost(gs, upgrade);
+    if gs.player_inventory.money < cost {
+        let mut log = gs.ecs.fetch_mut::<GameLog>();
+        log.add("You can't afford that");
+        return;
+    }
+
+    gs.player_inventory.money -= cost;
+
+    match upgrade {
+        Upgrade::Armor => {
+            gs.player_inventory.armor_level += 1;
+            update_armor(gs);
+
+            let mut log = gs.ecs.fetch_mut::<GameLog>();
+            log.add("The blacksmith reinforces your armor");
+        }
+        Upgrade::Ability { index, kind } => {
+            let weapon_type = gs.player_weapon.weapon_type;
+            let bonus = gs.player_inventory.get_bonus_mut(weapon_type, index);
+            match kind {
+                AbilityUpgrade::Stamina => bonus.stamina += 1,
+                AbilityUpgrade::Startup => bonus.startup += STARTUP_PER_LEVEL,
+                AbilityUpgrade::Radius => bonus.radius += 1,
+            }
+
+            gs.player_weapon = weapon::get_upgraded_weapon(weapon_type, &gs.player_inventory);
+
+            let mut log = gs.ecs.fetch_mut::<GameLog>();
+            log.add(format!(
+                "The blacksmith improves your {}",
+                gs.player_weapon.abilities[index].name.to_lowercase()
+            ));
+        }
+    }
+}
+
+fn update_armor(gs: &mut State) {
+    let player = gs.ecs.fetch::<Entity>();
+    let mut armors = gs.ecs.write_storage::<Armor>();
+    armors
+        .insert(
+            *player,
+            Armor {
+                amount: gs.player_inventory.armor_level as i32,
+            },
+        )
+        .expect("Failed to insert armor");
+}
@@ -0,0 +1,57 @@
+use super::{Weapon, WeaponType};
+use crate::{AttackBonus, AttackData, AttackType};
+
+#[derive(Copy, Clone)]
+pub enum BowAttack {
+    Shoot,
+    StunArrow,
+    Vault,
+}
+
+pub fn get_attack_data(attack: BowAttack) -> AttackData {
+    let needs_path = !matches!(attack, BowAttack::Vault);
+
+    let name = match attack {
+        BowAttack::Shoot => "Shoot",
+        BowAttack::StunArrow => "Stun Arrow",
+        BowAttack::Vault => "Vault",
+    }
+    .to_string();
+
+    let stam_cost = match attack {
+        BowAttack::Shoot => 1,
+        BowAttack::StunArrow => 3,
+        BowAttack::Vault => crate::player::DODGE_STAM_REQ,
+    };
+
+    let attack_type = match attack {
+        BowAttack::Shoot => AttackType::builtin("bow_shot"),
+        BowAttack::StunArrow => AttackType::builtin("bow_stun_shot"),
+        BowAttack::Vault => AttackType::builtin("dodge_3"),
+    };
+
+    AttackData {
+        needs_target: true,
+        needs_path,
+        name,
+        stam_cost,
+        attack_type,
+        bonus: AttackBonus::default(),
+    }
+}
+
+pub fn get_weapon() -> Weapon {
+    Weapon {
+        weapon_type: WeaponType::Bow,
+        name: "Bow".to_string(),
+        description: "Arrows from a distance, but weak up close".to_string(),
+        abilities: vec![
+            get_attack_data(BowAttack::Shoot),
+            get_attack_data(BowAttack::StunArrow),
+            get_attack_data(BowAttack::Vault),
+        ],
+        bump_attack: AttackType::builtin("melee_knockback"),
+        max_stamina: 8,
+        dodge_cost: crate::player::DODGE_STAM_REQ,
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Weapon, WeaponType};
+use crate::{AttackBonus, AttackData, AttackType};
+
+#[derive(Copy, Clone)]
+pub enum GauntletsAttack {
+    Flurry,
+    Uppercut,
+    Suplex,
+}
+
+pub fn get_attack_data(attack: GauntletsAttack) -> AttackData {
+    let name = match attack {
+        GauntletsAttack::Flurry => "Flurry",
+        GauntletsAttack::Uppercut => "Uppercut",
+        GauntletsAttack::Suplex => "Suplex",
+    }
+    .to_string();
+
+    let stam_cost = match attack {
+        GauntletsAttack::Flurry => 2,
+        GauntletsAttack::Uppercut => 3,
+        GauntletsAttack::Suplex => 2,
+    };
+
+    let attack_type = match attack {
+        GauntletsAttack::Flurry => AttackType::builtin("flurry"),
+        GauntletsAttack::Uppercut => AttackType::builtin("uppercut"),
+        GauntletsAttack::Suplex => AttackType::builtin("advancing_flip_2"),
+    };
+
+    AttackData {
+        needs_target: true,
+        needs_path: false,
+        name,
+        stam_cost,
+        attack_type,
+        bonus: AttackBonus::default(),
+    }
+}
+
+pub fn get_weapon() -> Weapon {
+    Weapon {
+        weapon_type: WeaponType::Gauntlets,
+        name: "Gauntlets".to_string(),
+        description: "Quick jabs with almost no windup".to_string(),
+        abilities: vec![
+            get_attack_data(GauntletsAttack::Flurry),
+            get_attack_data(GauntletsAttack::Uppercut),
+            get_attack_data(GauntletsAttack::Suplex),
+        ],
+        bump_attack: AttackType::builtin("jab"),
+        max_stamina: 10,
+        dodge_cost: 3,
+    }
+}
@@ -0,0 +1,58 @@
+use super::{Weapon, WeaponType};
+use crate::{AttackBonus, AttackData, AttackType};
+
+#[derive(Copy, Clone)]
+pub enum GreatswordAttack {
+    Sweep,
+    Cleave,
+    Shove,
+}
+
+pub fn get_attack_data(attack: GreatswordAttack) -> AttackData {
+    // sweeps hit everything around the player, so there is nothing to aim
+    let needs_target = matches!(attack, GreatswordAttack::Shove);
+
+    let name = match attack {
+        GreatswordAttack::Sweep => "Sweep",
+        GreatswordAttack::Cleave => "Cleave",
+        GreatswordAttack::Shove => "Shove",
+    }
+    .to_string();
+
+    let stam_cost = match attack {
+        GreatswordAttack::Sweep => 2,
+        GreatswordAttack::Cleave => 4,
+        GreatswordAttack::Shove => 3,
+    };
+
+    let attack_type = match attack {
+        GreatswordAttack::Sweep => AttackType::builtin("greatsword_sweep"),
+        GreatswordAttack::Cleave => AttackType::builtin("greatsword_cleave"),
+        GreatswordAttack::Shove => AttackType::builtin("shove"),
+    };
+
+    AttackData {
+        needs_target,
+        needs_path: false,
+        name,
+        stam_cost,
+        attack_type,
+        bonus: AttackBonus::default(),
+    }
+}
+
+pub fn get_weapon() -> Weapon {
+    Weapon {
+        weapon_type: WeaponType::Greatsword,
+        name: "Greatsword".to_string(),
+        description: "Slow, heavy sweeps that hit everything nearby".to_string(),
+        abilities: vec![
+            get_attack_data(GreatswordAttack::Sweep),
+            get_attack_data(GreatswordAttack::Cleave),
+            get_attack_data(GreatswordAttack::Shove),
+        ],
+        bump_attack: AttackType::builtin("melee2"),
+        max_stamina: 6,
+        dodge_cost: 6,
+    }
+}
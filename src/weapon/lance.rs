@@ -1,4 +1,5 @@
-use crate::{AttackData, AttackType};
+use super::{Weapon, WeaponType};
+use crate::{AttackBonus, AttackData, AttackType};
 
 #[derive(Copy, Clone)]
 pub enum LanceAttack {
@@ -38,14 +39,11 @@ pub fn get_attack_data(attack: LanceAttack) -> AttackData {
     };
 
     let attack_type = match attack {
-        LanceAttack::Sweep => AttackType::ProjectileArea {
-            radius: 4,
-            explosion_size: 1,
-        },
-        LanceAttack::DrawAttack => AttackType::AdvancingFlip { range: 2 },
-        LanceAttack::Hook => AttackType::Hook { radius: 5 },
-        LanceAttack::Charge => AttackType::Dodge { radius: 3 },
-        LanceAttack::Shove => AttackType::Shove,
+        LanceAttack::Sweep => AttackType::builtin("projectile_area_4_1"),
+        LanceAttack::DrawAttack => AttackType::builtin("advancing_flip_2"),
+        LanceAttack::Hook => AttackType::builtin("hook_5"),
+        LanceAttack::Charge => AttackType::builtin("dodge_3"),
+        LanceAttack::Shove => AttackType::builtin("shove"),
     };
 
     // TODO: Commenting this out so we don't use the wrong framedata
@@ -64,6 +62,25 @@ pub fn get_attack_data(attack: LanceAttack) -> AttackData {
         name,
         stam_cost,
         attack_type,
+        bonus: AttackBonus::default(),
         // frame_data,
     }
 }
+
+pub fn get_weapon() -> Weapon {
+    Weapon {
+        weapon_type: WeaponType::Lance,
+        name: "Lance".to_string(),
+        description: "Long reach, and tricks to pull foes in".to_string(),
+        abilities: vec![
+            get_attack_data(LanceAttack::DrawAttack),
+            get_attack_data(LanceAttack::Sweep),
+            get_attack_data(LanceAttack::Charge),
+            get_attack_data(LanceAttack::Hook),
+            get_attack_data(LanceAttack::Shove),
+        ],
+        bump_attack: AttackType::builtin("melee_knockback"),
+        max_stamina: 8,
+        dodge_cost: crate::player::DODGE_STAM_REQ,
+    }
+}
@@ -1 +1,66 @@
+use crate::inventory::Inventory;
+use crate::{AttackData, AttackType};
+use serde::{Deserialize, Serialize};
+
+pub mod bow;
+pub mod gauntlets;
+pub mod greatsword;
 pub mod lance;
+
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum WeaponType {
+    Lance,
+    Greatsword,
+    Gauntlets,
+    Bow,
+}
+
+pub const WEAPON_TYPES: [WeaponType; 4] = [
+    WeaponType::Lance,
+    WeaponType::Greatsword,
+    WeaponType::Gauntlets,
+    WeaponType::Bow,
+];
+
+impl WeaponType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        WEAPON_TYPES
+            .iter()
+            .copied()
+            .find(|weapon_type| get_weapon(*weapon_type).name.eq_ignore_ascii_case(name))
+    }
+}
+
+// Everything the player fights with. Swapping weapons swaps the whole moveset, along with how
+// much stamina the player has to spend
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Weapon {
+    pub weapon_type: WeaponType,
+    pub name: String,
+    pub description: String,
+    pub abilities: Vec<AttackData>,
+    pub bump_attack: AttackType,
+    pub max_stamina: i32,
+    pub dodge_cost: i32,
+}
+
+pub fn get_weapon(weapon_type: WeaponType) -> Weapon {
+    match weapon_type {
+        WeaponType::Lance => lance::get_weapon(),
+        WeaponType::Greatsword => greatsword::get_weapon(),
+        WeaponType::Gauntlets => gauntlets::get_weapon(),
+        WeaponType::Bow => bow::get_weapon(),
+    }
+}
+
+// the weapon with everything bought from the blacksmith applied
+pub fn get_upgraded_weapon(weapon_type: WeaponType, inventory: &Inventory) -> Weapon {
+    let mut weapon = get_weapon(weapon_type);
+
+    for (index, ability) in weapon.abilities.iter_mut().enumerate() {
+        ability.bonus = inventory.get_bonus(weapon_type, index);
+        ability.stam_cost = std::cmp::max(ability.stam_cost - ability.bonus.stamina, 0);
+    }
+
+    weapon
+}